@@ -0,0 +1,52 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use today_puzzle::{bitboard::BitBoard, bitpiece::BitPiece, piece::PIECE_TALL_L};
+
+pub fn placements(c: &mut Criterion) {
+    let mut group = c.benchmark_group("placements");
+    // Every orientation of the L pentomino, as the solver tries them
+    let variations = PIECE_TALL_L.0;
+
+    for (name, occupied) in [
+        ("empty", 0),
+        ("edges", 0x0306_0000_0000_0F7F),
+        ("nearly-full", 0xFFFF_F3E1_C1C3_FFFF),
+    ] {
+        let occupied = BitBoard::new(occupied);
+        group.bench_with_input(
+            BenchmarkId::new("to_bitboard", name),
+            &occupied,
+            |b, occupied| b.iter(|| each_position(black_box(&variations), *occupied)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("shift_table", name),
+            &occupied,
+            |b, occupied| b.iter(|| shift_table(black_box(&variations), *occupied)),
+        );
+    }
+}
+
+criterion_group!(benches, placements);
+criterion_main!(benches);
+
+/// Checks each position of each variation, as the solver originally did
+fn each_position(variations: &[BitPiece], occupied: BitBoard) -> usize {
+    let mut count = 0;
+    for variation in variations {
+        for x in 0..(9 - variation.width()) {
+            for y in 0..(9 - variation.height()) {
+                if !variation.to_bitboard(x, y).intersects(occupied) {
+                    count += 1;
+                }
+            }
+        }
+    }
+    count
+}
+
+/// Finds the free positions of each variation with [`BitPiece::placements`]
+fn shift_table(variations: &[BitPiece], occupied: BitBoard) -> usize {
+    variations
+        .iter()
+        .map(|variation| variation.placements(occupied).count())
+        .sum()
+}
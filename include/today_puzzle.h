@@ -0,0 +1,79 @@
+#ifndef TODAY_PUZZLE_H
+#define TODAY_PUZZLE_H
+
+/* Generated by cbindgen from src/ffi.rs - do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define TP_VARIANT_DRAGON_FJORD 0
+
+#define TP_VARIANT_JARRING_WORDS 1
+
+#define TP_VARIANT_CREA_MAKERSPACE 2
+
+#define TP_VARIANT_TETROMINO 3
+
+#define TP_VARIANT_WEEKDAY 4
+
+// Finds the solutions for a variant and date, one at a time
+typedef struct TpSolver TpSolver;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Number of variants, which are numbered from 0 (see the `TP_VARIANT_*` constants)
+uint32_t tp_variant_count(void);
+
+// Name of a variant (e.g. `dragon-fjord`), or NULL if the variant doesn't exist
+//
+// The string is static, so must not be freed.
+const char *tp_variant_name(uint32_t variant);
+
+// Creates a solver for a variant and date, or returns NULL if either doesn't exist
+//
+// Free the solver with [`tp_solver_free`].
+TpSolver *tp_solver_new(uint32_t variant, int32_t year, uint32_t month, uint32_t day);
+
+// Number of pieces in each of the solver's solutions
+//
+// # Safety
+//
+// `solver` must have been created by [`tp_solver_new`] and not yet freed.
+uintptr_t tp_solver_piece_count(const TpSolver *solver);
+
+// Finds the next solution, writing up to `len` piece bitmaps to `pieces`
+//
+// Returns the number of pieces in the solution, or 0 once every solution has been found.
+//
+// # Safety
+//
+// `solver` must have been created by [`tp_solver_new`] and not yet freed,
+// and `pieces` must be valid for writing `len` values.
+uintptr_t tp_solver_next(TpSolver *solver, uint64_t *pieces, uintptr_t len);
+
+// Number of solutions found so far
+//
+// # Safety
+//
+// `solver` must have been created by [`tp_solver_new`] and not yet freed.
+uint64_t tp_solver_count(const TpSolver *solver);
+
+// Frees a solver created by [`tp_solver_new`] (NULL is ignored)
+//
+// # Safety
+//
+// `solver` must be NULL, or created by [`tp_solver_new`] and not yet freed.
+void tp_solver_free(TpSolver *solver);
+
+// Counts the solutions for a variant and date, or returns -1 if either doesn't exist
+int64_t tp_count_solutions(uint32_t variant, int32_t year, uint32_t month, uint32_t day);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* TODAY_PUZZLE_H */
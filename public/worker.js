@@ -0,0 +1,71 @@
+// Finds solutions in the background, so the page stays responsive while browsing every solution
+//
+// Messages from the page:
+//   { type: 'solve', id, year, month, day, variant } - cancel any search in progress and start a new one
+//   { type: 'cancel' }                               - stop searching
+// Messages to the page:
+//   { type: 'variants', variants }                   - sent once loaded: { id, name, boardMask, labels, pieceCount } per variant
+//   { type: 'solution', id, cells, count }           - cells are the piece covering each square (or -1), row by row
+//   { type: 'done', id, count }                      - every solution has been found
+//   { type: 'error', id, message }
+import init, { SolutionStream, variants } from './pkg/today_puzzle.js'
+
+// Boards explored between checks for new messages
+const NODES_PER_SLICE = 20000n
+
+const ready = init().then(() => {
+  postMessage({
+    type: 'variants',
+    variants: variants().map((info) => {
+      const variant = {
+        id: info.id,
+        name: info.name,
+        boardMask: info.board_mask,
+        labels: info.labels(),
+        pieceCount: info.piece_count,
+      }
+      info.free()
+      return variant
+    }),
+  })
+})
+let stream = null
+let current = null
+
+self.onmessage = async (event) => {
+  const msg = event.data
+  await ready
+
+  current = null
+  if (stream) {
+    stream.cancel()
+    stream.free()
+    stream = null
+  }
+
+  if (msg.type === 'solve') {
+    try {
+      stream = new SolutionStream(msg.year, msg.month, msg.day, msg.variant)
+    } catch (e) {
+      postMessage({ type: 'error', id: msg.id, message: String(e) })
+      return
+    }
+    current = msg.id
+    search(msg.id, stream)
+  }
+}
+
+async function search(id, s) {
+  while (id === current && !s.is_done()) {
+    const solution = s.next_solution_within(NODES_PER_SLICE)
+    if (solution) {
+      postMessage({ type: 'solution', id, cells: solution.cells(), count: Number(s.count_so_far()) })
+      solution.free()
+    }
+    // Yield so that new solve and cancel messages are handled
+    await new Promise((resolve) => setTimeout(resolve))
+  }
+  if (id === current) {
+    postMessage({ type: 'done', id, count: Number(s.count_so_far()) })
+  }
+}
@@ -0,0 +1,385 @@
+//! HTTP service for solving puzzles, e.g. so dashboards can embed today's solution
+//!
+//! Usage: `today-is-server [ADDRESS] [DB]`, where the address defaults to `127.0.0.1:8080`,
+//! and DB is a solution database (created by `today-is build-db`) to answer from when it has the date.
+//!
+//! - `GET /solve/{variant}/{date}` returns the first solution as JSON
+//! - `GET /solve/{variant}/{date}.svg` returns the first solution as an SVG image
+//! - `GET /count/{variant}/{date}` returns the number of solutions as JSON
+//! - `POST /verify` checks a JSON body of `{"variant", "date", "solution"}`, with the solution in the printed text format
+//!   or as an emoji grid
+//!
+//! Dates can be anything the command line accepts, e.g. `2026-12-25`, `12-25`, `today`, or `next+friday`
+//! (percent-encoded, with `+` for spaces).
+//!
+//! Requests are answered by a fixed pool of worker threads (one per CPU), so long solves queue up
+//! rather than starving the host.
+
+use chrono::{Local, NaiveDate};
+use serde_json::{json, Value};
+use std::io::Read;
+use std::panic::{self, AssertUnwindSafe};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::thread;
+use tiny_http::{Header, Method, Request, Response, Server};
+use today_puzzle::board::Solution;
+use today_puzzle::date::parse_date;
+use today_puzzle::db::SolutionDb;
+use today_puzzle::emoji;
+use today_puzzle::render;
+use today_puzzle::variants::{
+    CreaMakerspace, DragonFjord, JarringWords, Tetromino, Variant, VariantId, Weekday,
+};
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+/// Largest `POST /verify` body that is read
+const MAX_BODY: u64 = 4096;
+
+/// Successful response body
+#[derive(Debug, PartialEq)]
+enum Reply {
+    Json(Value),
+    Svg(String),
+}
+
+/// Failed request, as an HTTP status and message
+#[derive(Debug, PartialEq)]
+struct Error(u16, String);
+
+impl Error {
+    fn bad_request(message: impl Into<String>) -> Error {
+        Error(400, message.into())
+    }
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let address = args.next().unwrap_or_else(|| DEFAULT_ADDRESS.to_owned());
+    if let Some(path) = args.next() {
+        match SolutionDb::load(&path) {
+            Ok(db) => {
+                eprintln!("Loaded {} boards from {path}", db.len());
+                db.install().ok();
+            }
+            Err(e) => {
+                eprintln!("Failed to read {path}: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+    let server = match Server::http(&address) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("Failed to listen on {address}: {e}");
+            std::process::exit(1);
+        }
+    };
+    eprintln!("Listening on http://{address}");
+
+    // Solving can take a while, so answer several requests at once, but no more than the CPUs can work on
+    let server = Arc::new(server);
+    let workers = thread::available_parallelism().map_or(4, |n| n.get());
+    let handles: Vec<_> = (0..workers)
+        .map(|_| {
+            let server = Arc::clone(&server);
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    respond(request);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().ok();
+    }
+}
+
+fn respond(mut request: Request) {
+    let reply = match read_body(&mut request) {
+        // A panic must not take down the worker thread, or the pool would shrink with every one
+        Ok(body) => panic::catch_unwind(AssertUnwindSafe(|| {
+            route(request.method(), request.url(), &body)
+        }))
+        .unwrap_or_else(|_| Err(Error(500, "Internal server error".into()))),
+        Err(e) => Err(e),
+    };
+
+    let (status, content_type, body) = match reply {
+        Ok(Reply::Json(value)) => (200, "application/json", value.to_string()),
+        Ok(Reply::Svg(svg)) => (200, "image/svg+xml", svg),
+        Err(Error(status, message)) => (
+            status,
+            "application/json",
+            json!({ "error": message }).to_string(),
+        ),
+    };
+    let header = Header::from_bytes("Content-Type", content_type).expect("valid header");
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(header);
+    if let Err(e) = request.respond(response) {
+        eprintln!("Failed to respond: {e}");
+    }
+}
+
+/// Reads the request body, failing if it is longer than [`MAX_BODY`]
+fn read_body(request: &mut Request) -> Result<String, Error> {
+    let too_large = || Error(413, format!("Request body is larger than {MAX_BODY} bytes"));
+    if request
+        .body_length()
+        .is_some_and(|len| len as u64 > MAX_BODY)
+    {
+        return Err(too_large());
+    }
+    // Read one byte more than allowed, to tell a body of exactly MAX_BODY bytes from a longer one
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(MAX_BODY + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| Error::bad_request(format!("Failed to read request body: {e}")))?;
+    if body.len() as u64 > MAX_BODY {
+        return Err(too_large());
+    }
+    String::from_utf8(body).map_err(|_| Error::bad_request("Request body is not UTF-8"))
+}
+
+fn route(method: &Method, url: &str, body: &str) -> Result<Reply, Error> {
+    let path = url.split('?').next().unwrap_or_default();
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match (method, &segments[..]) {
+        (Method::Get, ["solve", variant, date]) => match date.strip_suffix(".svg") {
+            Some(date) => solve(variant.parse()?, parse(date)?, true),
+            None => solve(variant.parse()?, parse(date)?, false),
+        },
+        (Method::Get, ["count", variant, date]) => Ok(count(variant.parse()?, parse(date)?)),
+        (Method::Post, ["verify"]) => verify(body),
+        (_, ["solve" | "count", _, _] | ["verify"]) => Err(Error(405, "Method not allowed".into())),
+        _ => Err(Error(404, format!("Not found: {path}"))),
+    }
+}
+
+impl From<&'static str> for Error {
+    fn from(message: &'static str) -> Error {
+        Error::bad_request(message)
+    }
+}
+
+/// Parses a date from a path segment, which may be percent-encoded (e.g. `next%20friday` or `next+friday`)
+fn parse(date: &str) -> Result<NaiveDate, Error> {
+    parse_date(&percent_decode(date)?, Local::now().date_naive())
+        .map_err(|e| Error::bad_request(e.to_string()))
+}
+
+/// Decodes `%XX` escapes, and `+` as a space
+fn percent_decode(s: &str) -> Result<String, Error> {
+    let invalid = || Error::bad_request(format!("Invalid percent-encoding: {s}"));
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        match byte {
+            b'%' => {
+                let hex = rest.get(..2).ok_or_else(invalid)?;
+                let hex = std::str::from_utf8(hex).map_err(|_| invalid())?;
+                bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+                rest = &rest[2..];
+            }
+            b'+' => bytes.push(b' '),
+            byte => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+fn solve(variant: VariantId, date: NaiveDate, svg: bool) -> Result<Reply, Error> {
+    match variant {
+        VariantId::DragonFjord => solve_as::<DragonFjord, 8>(date, svg),
+        VariantId::JarringWords => solve_as::<JarringWords, 8>(date, svg),
+        VariantId::CreaMakerspace => solve_as::<CreaMakerspace, 8>(date, svg),
+        VariantId::Tetromino => solve_as::<Tetromino, 9>(date, svg),
+        VariantId::Weekday => solve_as::<Weekday, 10>(date, svg),
+    }
+}
+
+fn count(variant: VariantId, date: NaiveDate) -> Reply {
+    let count = match variant {
+        VariantId::DragonFjord => DragonFjord::count(date),
+        VariantId::JarringWords => JarringWords::count(date),
+        VariantId::CreaMakerspace => CreaMakerspace::count(date),
+        VariantId::Tetromino => Tetromino::count(date),
+        VariantId::Weekday => Weekday::count(date),
+    };
+    Reply::Json(json!({
+        "variant": variant.name(),
+        "date": date.to_string(),
+        "count": count,
+    }))
+}
+
+fn verify(body: &str) -> Result<Reply, Error> {
+    let request: Value =
+        serde_json::from_str(body).map_err(|e| Error::bad_request(format!("Invalid JSON: {e}")))?;
+    let field = |name| {
+        request[name]
+            .as_str()
+            .ok_or_else(|| Error::bad_request(format!("Missing string field: {name}")))
+    };
+    let variant: VariantId = field("variant")?.parse()?;
+    let date = parse(field("date")?)?;
+
+    let solution = field("solution")?;
+    let solution = if solution.is_ascii() {
+        Solution::from_str(solution)
+    } else {
+        emoji::parse(solution)
+    };
+    let result = solution.and_then(|solution| match variant {
+        VariantId::DragonFjord => check::<DragonFjord, 8>(date, &solution),
+        VariantId::JarringWords => check::<JarringWords, 8>(date, &solution),
+        VariantId::CreaMakerspace => check::<CreaMakerspace, 8>(date, &solution),
+        VariantId::Tetromino => check::<Tetromino, 9>(date, &solution),
+        VariantId::Weekday => check::<Weekday, 10>(date, &solution),
+    });
+    Ok(Reply::Json(json!({
+        "variant": variant.name(),
+        "date": date.to_string(),
+        "valid": result.is_ok(),
+        "error": result.err(),
+    })))
+}
+
+fn solve_as<V: Variant<N>, const N: usize>(date: NaiveDate, svg: bool) -> Result<Reply, Error> {
+    let solution = V::solve_once(date);
+    if svg {
+        let solution = solution.ok_or_else(|| Error(404, format!("{date} has no solutions")))?;
+        return Ok(Reply::Svg(render::svg::<V, N>(&solution)));
+    }
+    Ok(Reply::Json(json!({
+        "variant": V::NAME,
+        "date": date.to_string(),
+        "solvable": solution.is_some(),
+        "solution": solution.map(|s| solution_rows(&s)),
+    })))
+}
+
+fn check<V: Variant<N>, const N: usize>(
+    date: NaiveDate,
+    solution: &Solution,
+) -> Result<(), &'static str> {
+    V::board(date).check_solution(&V::pieces(), solution)
+}
+
+/// Rows of a solution, e.g. `AAGGGG..`
+fn solution_rows(solution: &Solution) -> Vec<String> {
+    solution
+        .to_string()
+        .lines()
+        .map(|row| row.split_whitespace().collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(url: &str) -> Result<Reply, Error> {
+        route(&Method::Get, url, "")
+    }
+
+    fn json(reply: Result<Reply, Error>) -> Value {
+        match reply {
+            Ok(Reply::Json(value)) => value,
+            other => panic!("expected JSON, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn solve_and_count() {
+        let solved = json(get("/solve/dragon-fjord/2020-12-25"));
+        assert_eq!(solved["solvable"], true);
+        assert_eq!(solved["solution"][0], "AAGGGG..");
+        let counted = json(get("/count/dragon-fjord/2020-12-25?pretty"));
+        assert_eq!(counted["count"], 92);
+        assert_eq!(counted["date"], "2020-12-25");
+
+        match get("/solve/tetromino/2020-12-25.svg") {
+            Ok(Reply::Svg(svg)) => assert!(svg.starts_with("<svg")),
+            other => panic!("expected SVG, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn verify_solutions() {
+        let rows = json(get("/solve/dragon-fjord/2020-12-25"))["solution"].clone();
+        let text: Vec<String> = rows
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|row| {
+                row.as_str()
+                    .unwrap()
+                    .chars()
+                    .map(|c| format!("{c} "))
+                    .collect()
+            })
+            .collect();
+        let body = |date: &str| {
+            json!({ "variant": "dragon-fjord", "date": date, "solution": text.join("\n") })
+                .to_string()
+        };
+
+        let valid = json(route(&Method::Post, "/verify", &body("2020-12-25")));
+        assert_eq!(valid["valid"], true);
+        assert_eq!(valid["error"], Value::Null);
+        let invalid = json(route(&Method::Post, "/verify", &body("2020-12-24")));
+        assert_eq!(invalid["valid"], false);
+        assert!(invalid["error"].is_string());
+
+        // A piece left out of the grid
+        let missing = text.join("\n").replace('B', ".");
+        let body = json!({ "variant": "dragon-fjord", "date": "2020-12-25", "solution": missing });
+        let invalid = json(route(&Method::Post, "/verify", &body.to_string()));
+        assert_eq!(invalid["valid"], false);
+        assert_eq!(invalid["error"], "Piece is missing");
+
+        let date = NaiveDate::from_ymd_opt(2020, 12, 25).unwrap();
+        let grid = emoji::render::<DragonFjord, 8>(&DragonFjord::solve_once(date).unwrap());
+        let body = json!({ "variant": "dragon-fjord", "date": "2020-12-25", "solution": grid });
+        let valid = json(route(&Method::Post, "/verify", &body.to_string()));
+        assert_eq!(valid["valid"], true);
+    }
+
+    #[test]
+    fn encoded_dates() {
+        for date in [
+            "2020-12-25",
+            "dec%2025%202020",
+            "dec+25+2020",
+            "2020%2F12%2F25",
+            "2020%2d12%2D25",
+        ] {
+            let counted = json(get(&format!("/count/dragon-fjord/{date}")));
+            assert_eq!(counted["date"], "2020-12-25", "{date}");
+        }
+        assert_eq!(percent_decode("next+friday"), Ok("next friday".to_owned()));
+        assert_eq!(get("/count/dragon-fjord/12-25%2").unwrap_err().0, 400);
+        assert_eq!(get("/count/dragon-fjord/%zz").unwrap_err().0, 400);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(get("/solve/dragon-fjord/feb%2030").unwrap_err().0, 400);
+        assert_eq!(get("/solve/pentomino/2020-12-25").unwrap_err().0, 400);
+        assert_eq!(get("/solve/dragon-fjord").unwrap_err().0, 404);
+        assert_eq!(
+            route(&Method::Post, "/count/weekday/today", "")
+                .unwrap_err()
+                .0,
+            405
+        );
+        assert_eq!(route(&Method::Post, "/verify", "{}").unwrap_err().0, 400);
+    }
+}
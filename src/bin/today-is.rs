@@ -0,0 +1,875 @@
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use chrono::{Datelike, Days, Local, NaiveDate};
+use clap::Parser;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use today_puzzle::bitpiece::BitPiece;
+use today_puzzle::board::Solution;
+use today_puzzle::calendar::{WeekdayTable, WEEKDAYS};
+use today_puzzle::challenge::Challenge;
+use today_puzzle::date::{parse_date, DateParseError};
+use today_puzzle::db::SolutionDb;
+use today_puzzle::designer::Designer;
+use today_puzzle::digest::Digest;
+use today_puzzle::emoji;
+use today_puzzle::piece::Piece;
+use today_puzzle::polyomino::{self, Polyomino};
+use today_puzzle::render;
+use today_puzzle::solver::{PieceOrder, SolveOptions, Solver};
+use today_puzzle::variants::{
+    BoardDate, CreaMakerspace, DragonFjord, JarringWords, Tetromino, Variant, Weekday,
+};
+
+/// Exit code when any date has no solutions
+const EXIT_UNSOLVABLE: u8 = 3;
+
+/// How often `--checkpoint` saves progress
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Date to solve, e.g. 2026-12-25, 12-25, tomorrow, +3, next friday, dec 25, or 2026-W52-5 [default: today]
+    #[arg(short, long, global = true)]
+    date: Option<LazyDate>,
+
+    /// Count solutions for every day of the year
+    #[arg(short, long, global = true)]
+    all_dates: bool,
+
+    /// First date of a range to solve [default: today]
+    #[arg(long, global = true, conflicts_with_all = ["date", "all_dates", "month", "year"])]
+    from: Option<LazyDate>,
+
+    /// Last date of a range to solve [default: --from]
+    #[arg(long, global = true, conflicts_with_all = ["date", "all_dates", "month", "year"])]
+    to: Option<LazyDate>,
+
+    /// Solve every day of this month (1-12) of `--year`
+    #[arg(long, global = true, value_parser = clap::value_parser!(u32).range(1..=12), conflicts_with_all = ["date", "all_dates"])]
+    month: Option<u32>,
+
+    /// Solve every day of this year (or of `--month` in this year) [default: current year]
+    #[arg(long, global = true, conflicts_with_all = ["date", "all_dates"])]
+    year: Option<i32>,
+
+    /// Print min/max/mean/median solution counts across all dates solved
+    #[arg(short, long, global = true)]
+    stats: bool,
+
+    /// Specifies with solutions to print
+    #[arg(short, long, value_enum, global = true, default_value_t=Print::First)]
+    print: Print,
+
+    /// Puzzle variant
+    #[arg(short, long, value_enum, global = true, default_value_t=VariantOpt::DragonFjord)]
+    variant: VariantOpt,
+
+    /// Periodically save `--all-dates --print count` progress to this file, resuming from it if it exists
+    #[arg(long, global = true, requires = "all_dates")]
+    checkpoint: Option<PathBuf>,
+
+    /// Print one JSON object per date (variant, date, solvable, count, solutions, elapsed_ms)
+    #[arg(long, global = true, conflicts_with = "stats")]
+    json: bool,
+
+    /// Answer from a solution database (created by `build-db`) when it has the date
+    #[arg(long, global = true)]
+    db: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Solve the selected dates, printing solutions according to `--print` (the default command)
+    Solve,
+    /// Count solutions for the selected dates (same as `--print count`)
+    Count,
+    /// Check that the selected dates have solutions (same as `--print check`)
+    Check,
+    /// Solve every date of a year
+    Year {
+        /// Year to solve [default: current year]
+        #[arg(value_name = "YEAR")]
+        value: Option<i32>,
+    },
+    /// Render the first solution for `--date` as text, SVG, or emoji
+    Render {
+        #[arg(short, long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+
+        /// File to write [default: stdout]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Check that a solution (in the format it is printed, or as emoji) solves `--date`
+    Verify {
+        /// File containing the solution [default: stdin]
+        solution: Option<PathBuf>,
+    },
+    /// Precompute solutions for every variant and date into a solution database
+    BuildDb {
+        /// Database file to write
+        output: PathBuf,
+
+        /// Store every solution instead of only the first solution and count
+        #[arg(long)]
+        full: bool,
+    },
+    /// Recompute every entry of a solution database and report any mismatches
+    VerifyDb {
+        /// Database file to verify
+        db: PathBuf,
+    },
+    /// Generate a "hard mode" challenge for `--date` with pieces pre-placed so only one solution remains
+    Challenge,
+    /// Search for sets of pieces that solve every date on the board of `--variant`
+    Design {
+        /// Candidate piece shapes as comma-separated 4x4 hex bitmaps (e.g. 0x0313,0x0117)
+        #[arg(long, value_delimiter = ',', value_parser = parse_bitpiece)]
+        pool: Vec<BitPiece>,
+
+        /// Add every polyomino of these sizes that fits within 4x4 to the pool (e.g. 5 for pentominoes)
+        #[arg(long, value_delimiter = ',')]
+        polyominoes: Vec<usize>,
+
+        /// Number of pieces in each set
+        #[arg(short = 'n', long)]
+        pieces: usize,
+
+        /// Stop searching after finding this many sets
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Solve the board squares that are not calendar dates (e.g. Feb 30) as bonus challenges
+    Bonus,
+    /// Find impossible (month, day, weekday) combinations of the weekday variant and the dates they fall on
+    Weekdays {
+        /// Load the table of every combination from this file, or compute and save it if it does not exist
+        #[arg(long)]
+        table: Option<PathBuf>,
+
+        /// Years to list impossible dates for [default: current year]
+        years: Vec<i32>,
+    },
+    /// Summarize `--date` for every variant, e.g. to post in a chat
+    ///
+    /// Difficulty ranks the date's solution count within its year, and is only shown
+    /// with a `--db` that has every date of the year.
+    Digest {
+        #[arg(short, long, value_enum, default_value_t = DigestFormat::Markdown)]
+        format: DigestFormat,
+    },
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum Format {
+    /// Piece letters, as printed by `solve`
+    Text,
+    /// Colored pieces with the date labeled
+    Svg,
+    /// Rows of colored square emoji, e.g. to share in a chat
+    Emoji,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum DigestFormat {
+    /// Table with the solution grid behind a spoiler tag
+    Markdown,
+    /// Aligned lines with the solution grid after a spoiler warning
+    Text,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum Print {
+    /// Display first solution, but no count (fastest)
+    First,
+    /// Display first solution and count of solutions
+    Summary,
+    /// Display all solutions and count of solutions
+    All,
+    /// Display only the count of solutions
+    Count,
+    /// Only prints indicator if solution exists (stops early if any day is unsolvable)
+    Check,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum VariantOpt {
+    DragonFjord,
+    CreaMakerspace,
+    JarringWords,
+    Tetromino,
+    Weekday,
+}
+
+impl VariantOpt {
+    fn name(self) -> &'static str {
+        match self {
+            VariantOpt::DragonFjord => DragonFjord::NAME,
+            VariantOpt::CreaMakerspace => CreaMakerspace::NAME,
+            VariantOpt::JarringWords => JarringWords::NAME,
+            VariantOpt::Tetromino => Tetromino::NAME,
+            VariantOpt::Weekday => Weekday::NAME,
+        }
+    }
+}
+
+fn parse_bitpiece(s: &str) -> Result<BitPiece> {
+    let shape = u16::from_str_radix(s.trim().trim_start_matches("0x"), 16)
+        .with_context(|| format!("invalid piece bitmap: {s}"))?;
+    ensure!(shape != 0, "piece bitmap must not be empty");
+    Ok(BitPiece::new(shape))
+}
+
+// Date structure that we can parse as Y-M-D, M-D, or a relative date (see `parse_date`)
+#[derive(Clone, Copy, Debug)]
+struct LazyDate(NaiveDate);
+
+impl LazyDate {
+    fn today() -> LazyDate {
+        let d = Local::now().date_naive();
+        LazyDate(d)
+    }
+}
+
+impl From<NaiveDate> for LazyDate {
+    fn from(d: NaiveDate) -> LazyDate {
+        LazyDate(d)
+    }
+}
+
+impl FromStr for LazyDate {
+    type Err = DateParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let LazyDate(today) = LazyDate::today();
+        parse_date(s, today).map(LazyDate)
+    }
+}
+
+fn main() -> Result<ExitCode> {
+    let mut args = Args::parse();
+
+    if let Some(path) = &args.db {
+        let db = SolutionDb::load(path).with_context(|| format!("reading {}", path.display()))?;
+        db.install().ok();
+    }
+
+    match &args.command {
+        Some(Command::BuildDb { output, full }) => {
+            let mut db = SolutionDb::new();
+            build_db::<DragonFjord, 8>(&mut db, *full);
+            build_db::<CreaMakerspace, 8>(&mut db, *full);
+            build_db::<JarringWords, 8>(&mut db, *full);
+            build_db::<Tetromino, 9>(&mut db, *full);
+            build_db::<Weekday, 10>(&mut db, *full);
+            db.save(output)
+                .with_context(|| format!("writing {}", output.display()))?;
+            println!("Wrote {} entries to {}", db.len(), output.display());
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Command::VerifyDb { db }) => {
+            let db = SolutionDb::load(db).with_context(|| format!("reading {}", db.display()))?;
+            let mismatches = verify_db::<DragonFjord, 8>(&db)
+                + verify_db::<CreaMakerspace, 8>(&db)
+                + verify_db::<JarringWords, 8>(&db)
+                + verify_db::<Tetromino, 9>(&db)
+                + verify_db::<Weekday, 10>(&db);
+            ensure!(
+                mismatches == 0,
+                "{mismatches} of {} entries do not match",
+                db.len()
+            );
+            println!("All {} entries match", db.len());
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Command::Challenge) => {
+            let LazyDate(date) = args.date.unwrap_or_else(LazyDate::today);
+            match args.variant {
+                VariantOpt::DragonFjord => print_challenge::<DragonFjord, 8>(date)?,
+                VariantOpt::CreaMakerspace => print_challenge::<CreaMakerspace, 8>(date)?,
+                VariantOpt::JarringWords => print_challenge::<JarringWords, 8>(date)?,
+                VariantOpt::Tetromino => print_challenge::<Tetromino, 9>(date)?,
+                VariantOpt::Weekday => print_challenge::<Weekday, 10>(date)?,
+            }
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Command::Design {
+            pool,
+            polyominoes,
+            pieces,
+            limit,
+        }) => {
+            let mut pool = pool.clone();
+            for &n in polyominoes {
+                ensure!(
+                    n <= polyomino::MAX_CELLS,
+                    "polyominoes are limited to {} squares",
+                    polyomino::MAX_CELLS
+                );
+                pool.extend(
+                    polyomino::free(n)
+                        .into_iter()
+                        .filter_map(Polyomino::to_bitpiece),
+                );
+            }
+            ensure!(
+                !pool.is_empty(),
+                "no candidate pieces (use --pool or --polyominoes)"
+            );
+            let designer = match args.variant {
+                VariantOpt::DragonFjord => Designer::for_variant::<DragonFjord, 8>(pool),
+                VariantOpt::CreaMakerspace => Designer::for_variant::<CreaMakerspace, 8>(pool),
+                VariantOpt::JarringWords => Designer::for_variant::<JarringWords, 8>(pool),
+                VariantOpt::Tetromino => Designer::for_variant::<Tetromino, 9>(pool),
+                VariantOpt::Weekday => Designer::for_variant::<Weekday, 10>(pool),
+            };
+            let designs = match pieces {
+                6 => designer.search::<6>(*limit),
+                7 => designer.search::<7>(*limit),
+                8 => designer.search::<8>(*limit),
+                9 => designer.search::<9>(*limit),
+                10 => designer.search::<10>(*limit),
+                11 => designer.search::<11>(*limit),
+                12 => designer.search::<12>(*limit),
+                n => bail!("sets of {n} pieces are not supported (must be 6-12)"),
+            };
+
+            println!(
+                "Found {} sets covering {} squares",
+                designs.len(),
+                designer.area()
+            );
+            for design in designs {
+                let shapes: Vec<String> = design
+                    .pieces
+                    .iter()
+                    .map(|&i| format!("{:#06x}", designer.pool()[i].0))
+                    .collect();
+                println!(
+                    "min {} mean {:.1}: {}",
+                    design.min_solutions,
+                    design.mean_solutions,
+                    shapes.join(",")
+                );
+            }
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Command::Bonus) => {
+            let dates = (1..=12)
+                .flat_map(|month| (1..=31).map(move |day| BoardDate::new(month, day)))
+                .filter(|date| !date.is_calendar_date());
+            let mut unsolvable = false;
+            for date in dates {
+                let name = format!("{:02}-{:02}", date.month, date.day);
+                if matches!(args.variant, VariantOpt::Weekday) {
+                    // Weekday boards also need a weekday square uncovered, so try each one
+                    for weekday in
+                        std::iter::successors(Some(chrono::Weekday::Sun), |d| Some(d.succ()))
+                            .take(7)
+                    {
+                        let date = date.with_weekday(weekday);
+                        let name = format!("{name} {weekday}");
+                        unsolvable |=
+                            solve_and_print(args.variant, date, &name, args.print, args.json)? == 0;
+                    }
+                } else {
+                    unsolvable |=
+                        solve_and_print(args.variant, date, &name, args.print, args.json)? == 0;
+                }
+            }
+            if unsolvable {
+                return Ok(ExitCode::from(EXIT_UNSOLVABLE));
+            }
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Command::Weekdays { table, years }) => {
+            let table = match table {
+                Some(path) if path.exists() => WeekdayTable::load(path)
+                    .with_context(|| format!("reading {}", path.display()))?,
+                Some(path) => {
+                    let table = compute_weekday_table();
+                    table
+                        .save(path)
+                        .with_context(|| format!("writing {}", path.display()))?;
+                    table
+                }
+                None => compute_weekday_table(),
+            };
+            print_weekday_table(&table, years);
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Command::Digest { format }) => {
+            let LazyDate(date) = args.date.unwrap_or_else(LazyDate::today);
+            let digest = Digest::new(date);
+            match format {
+                DigestFormat::Markdown => print!("{}", digest.to_markdown()),
+                DigestFormat::Text => print!("{}", digest.to_text()),
+            }
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Command::Render { format, output }) => {
+            let LazyDate(date) = args.date.unwrap_or_else(LazyDate::today);
+            let rendered = match args.variant {
+                VariantOpt::DragonFjord => render_solution::<DragonFjord, 8>(date, *format)?,
+                VariantOpt::CreaMakerspace => render_solution::<CreaMakerspace, 8>(date, *format)?,
+                VariantOpt::JarringWords => render_solution::<JarringWords, 8>(date, *format)?,
+                VariantOpt::Tetromino => render_solution::<Tetromino, 9>(date, *format)?,
+                VariantOpt::Weekday => render_solution::<Weekday, 10>(date, *format)?,
+            };
+            match output {
+                Some(path) => fs::write(path, rendered)
+                    .with_context(|| format!("writing {}", path.display()))?,
+                None => print!("{rendered}"),
+            }
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Command::Verify { solution }) => {
+            let text = match solution {
+                Some(path) => fs::read_to_string(path)
+                    .with_context(|| format!("reading {}", path.display()))?,
+                None => io::read_to_string(io::stdin()).context("reading stdin")?,
+            };
+            let solution = if text.is_ascii() {
+                text.parse()
+            } else {
+                emoji::parse(&text)
+            };
+            let solution = solution.map_err(anyhow::Error::msg)?;
+            let LazyDate(date) = args.date.unwrap_or_else(LazyDate::today);
+            let board = match args.variant {
+                VariantOpt::DragonFjord => check_solution::<DragonFjord, 8>(date, &solution),
+                VariantOpt::CreaMakerspace => check_solution::<CreaMakerspace, 8>(date, &solution),
+                VariantOpt::JarringWords => check_solution::<JarringWords, 8>(date, &solution),
+                VariantOpt::Tetromino => check_solution::<Tetromino, 9>(date, &solution),
+                VariantOpt::Weekday => check_solution::<Weekday, 10>(date, &solution),
+            };
+            board.map_err(|e| anyhow!("{e} for {:02}-{:02}", date.month(), date.day()))?;
+            println!(
+                "Solution is valid for {:02}-{:02}",
+                date.month(),
+                date.day()
+            );
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Command::Year { value }) => {
+            args.year = Some(value.unwrap_or_else(|| Local::now().year()));
+            args.month = None;
+        }
+        Some(Command::Solve | Command::Count | Command::Check) | None => {}
+    }
+
+    let print = match args.command {
+        Some(Command::Count) => Print::Count,
+        Some(Command::Check) => Print::Check,
+        _ => args.print,
+    };
+
+    if let Some(path) = &args.checkpoint {
+        ensure!(
+            matches!(print, Print::Count),
+            "--checkpoint only supports `--print count`"
+        );
+        ensure!(!args.json, "--checkpoint does not support --json");
+        match args.variant {
+            VariantOpt::DragonFjord => count_resumable::<DragonFjord, 8>(args.variant, path)?,
+            VariantOpt::CreaMakerspace => count_resumable::<CreaMakerspace, 8>(args.variant, path)?,
+            VariantOpt::JarringWords => count_resumable::<JarringWords, 8>(args.variant, path)?,
+            VariantOpt::Tetromino => count_resumable::<Tetromino, 9>(args.variant, path)?,
+            VariantOpt::Weekday => count_resumable::<Weekday, 10>(args.variant, path)?,
+        }
+    } else {
+        ensure!(
+            !args.stats || matches!(print, Print::Count | Print::Summary | Print::All),
+            "--stats requires `--print count`, `--print summary`, or `--print all`"
+        );
+        let mut counts = Vec::new();
+        for date in dates(&args)? {
+            let name = date.to_string();
+            let count = solve_and_print(args.variant, date.into(), &name, print, args.json)?;
+            counts.push((date, count));
+            if count == 0 && matches!(print, Print::Check) {
+                break;
+            }
+        }
+        if args.stats {
+            print_stats(&counts);
+        }
+        if counts.iter().any(|&(_, count)| count == 0) {
+            return Ok(ExitCode::from(EXIT_UNSOLVABLE));
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The dates selected by `--date`, `--all-dates`, `--from`/`--to`, or `--month`/`--year`
+fn dates(args: &Args) -> Result<Vec<NaiveDate>> {
+    let (first, last) = if args.all_dates {
+        (ymd(2020, 1, 1)?, ymd(2020, 12, 31)?)
+    } else if args.from.is_some() || args.to.is_some() {
+        let LazyDate(first) = args.from.unwrap_or_else(LazyDate::today);
+        let LazyDate(last) = args.to.unwrap_or(LazyDate(first));
+        (first, last)
+    } else if args.month.is_some() || args.year.is_some() {
+        let year = args.year.unwrap_or_else(|| Local::now().year());
+        match args.month {
+            Some(12) => (ymd(year, 12, 1)?, ymd(year, 12, 31)?),
+            Some(m) => (ymd(year, m, 1)?, ymd(year, m + 1, 1)?.pred_opt().unwrap()),
+            None => (ymd(year, 1, 1)?, ymd(year, 12, 31)?),
+        }
+    } else {
+        let LazyDate(date) = args.date.unwrap_or_else(LazyDate::today);
+        (date, date)
+    };
+
+    ensure!(first <= last, "{first} is after {last}");
+    Ok(first.iter_days().take_while(|d| *d <= last).collect())
+}
+
+fn ymd(year: i32, month: u32, day: u32) -> Result<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, day)
+        .with_context(|| format!("invalid date {year}-{month}-{day}"))
+}
+
+/// Prints min/max/mean/median of the solution counts
+fn print_stats(counts: &[(NaiveDate, usize)]) {
+    let Some(&(min_date, min)) = counts.iter().min_by_key(|(_, count)| *count) else {
+        return;
+    };
+    let &(max_date, max) = counts.iter().max_by_key(|(_, count)| *count).unwrap();
+    let mut sorted: Vec<usize> = counts.iter().map(|(_, count)| *count).collect();
+    sorted.sort_unstable();
+    let mid = sorted.len() / 2;
+    let median = if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) as f64 / 2.0
+    } else {
+        sorted[mid] as f64
+    };
+    let mean = sorted.iter().sum::<usize>() as f64 / sorted.len() as f64;
+
+    println!("**** {} dates ****", counts.len());
+    println!("min: {min} solutions ({min_date})");
+    println!("max: {max} solutions ({max_date})");
+    println!("mean: {mean:.1} solutions");
+    println!("median: {median:.1} solutions");
+}
+
+/// Solves and prints a date according to the print mode, returning the solution count
+///
+/// The count is only complete for print modes that count solutions.
+/// With `json`, prints a single JSON object for the date (named `name`) instead of text.
+fn solve_and_print(
+    variant: VariantOpt,
+    date: BoardDate,
+    name: &str,
+    print: Print,
+    json: bool,
+) -> Result<usize> {
+    let label = format!("{:02}-{:02}", date.month, date.day);
+    if !json && !matches!(print, Print::Count | Print::Check) {
+        println!("**** {label} ****");
+    }
+
+    let start = Instant::now();
+    let (solutions, count) = match variant {
+        VariantOpt::DragonFjord => solve::<DragonFjord, 8>(date, print),
+        VariantOpt::CreaMakerspace => solve::<CreaMakerspace, 8>(date, print),
+        VariantOpt::JarringWords => solve::<JarringWords, 8>(date, print),
+        VariantOpt::Tetromino => solve::<Tetromino, 9>(date, print),
+        VariantOpt::Weekday => solve::<Weekday, 10>(date, print),
+    }
+    .map_err(anyhow::Error::msg)?;
+    let elapsed = start.elapsed();
+
+    if json {
+        let rows: Vec<Vec<String>> = match print {
+            Print::First | Print::Summary => solutions.iter().take(1).map(solution_rows).collect(),
+            Print::All => solutions.iter().map(solution_rows).collect(),
+            Print::Count | Print::Check => Vec::new(),
+        };
+        // Only some print modes count every solution
+        let full_count = match print {
+            Print::Summary | Print::All | Print::Count => Some(count),
+            Print::First | Print::Check => None,
+        };
+        let line = serde_json::json!({
+            "variant": variant.name(),
+            "date": name,
+            "solvable": count > 0,
+            "count": full_count,
+            "solutions": rows,
+            "elapsed_ms": elapsed.as_secs_f64() * 1000.0,
+        });
+        println!("{line}");
+        return Ok(count);
+    }
+
+    for solution in &solutions {
+        match print {
+            Print::First | Print::Summary => {
+                println!("{solution}");
+                break;
+            }
+            Print::All => println!("{solution}"),
+            Print::Count | Print::Check => {}
+        }
+    }
+
+    match print {
+        Print::First | Print::Check if count == 0 => println!("{label} has NO solutions"),
+        Print::First => {}
+        Print::Check => println!("{label} has solutions"),
+        Print::All | Print::Summary | Print::Count => println!("{label} has {count} solutions"),
+    }
+    Ok(count)
+}
+
+/// Rows of a solution without spaces (e.g. `AAGGGG..`), for JSON output
+fn solution_rows(solution: &Solution) -> Vec<String> {
+    solution
+        .to_string()
+        .lines()
+        .map(|row| row.split_whitespace().collect())
+        .collect()
+}
+
+/// Finds the solutions needed by the print mode, along with the solution count
+fn solve<V: Variant<N>, const N: usize>(
+    date: BoardDate,
+    print: Print,
+) -> Result<(Vec<Solution>, usize), &'static str> {
+    Ok(match print {
+        Print::First | Print::Check => {
+            let solutions: Vec<_> = V::solve_once_at(date)?.into_iter().collect();
+            let count = solutions.len();
+            (solutions, count)
+        }
+        Print::Summary => (
+            V::solve_once_at(date)?.into_iter().collect(),
+            V::count_at(date)?,
+        ),
+        Print::Count => (Vec::new(), V::count_at(date)?),
+        Print::All => {
+            let solutions = V::solve_fully_at(date)?;
+            let count = solutions.len();
+            (solutions, count)
+        }
+    })
+}
+
+/// Renders the first solution for a date
+fn render_solution<V: Variant<N>, const N: usize>(
+    date: NaiveDate,
+    format: Format,
+) -> Result<String> {
+    let solution = V::solve_once(date).with_context(|| format!("{date} has no solutions"))?;
+    Ok(match format {
+        Format::Text => format!("{solution}\n"),
+        Format::Svg => render::svg::<V, N>(&solution),
+        Format::Emoji => emoji::render::<V, N>(&solution),
+    })
+}
+
+fn check_solution<V: Variant<N>, const N: usize>(
+    date: NaiveDate,
+    solution: &Solution,
+) -> Result<(), &'static str> {
+    V::board(date).check_solution(&V::pieces(), solution)
+}
+
+/// Precomputes solutions for every month, day, and weekday on the board, so the database has every date of any year
+///
+/// Variants without weekday squares share a board across weekdays, which is only solved once.
+fn build_db<V: Variant<N>, const N: usize>(db: &mut SolutionDb, full: bool) {
+    eprintln!("Building {}", V::NAME);
+    for month in 1..=12 {
+        for day in 1..=31 {
+            for weekday in WEEKDAYS {
+                let date = BoardDate::new(month, day).with_weekday(weekday);
+                let board = V::board_at(date).expect("every month and day is on the board");
+                if !db.contains_board::<V, N>(board) {
+                    db.record_board::<V, N>(board, full);
+                }
+            }
+        }
+    }
+}
+
+fn print_challenge<V: Variant<N>, const N: usize>(date: NaiveDate) -> Result<()> {
+    let challenge =
+        Challenge::generate::<V, N>(date).with_context(|| format!("{date} has no solutions"))?;
+    let board = challenge.board::<V, N>().map_err(anyhow::Error::msg)?;
+    println!("{challenge}");
+    println!("{board}");
+    Ok(())
+}
+
+/// Computes the weekday table, printing progress after each month
+fn compute_weekday_table() -> WeekdayTable {
+    WeekdayTable::compute_with(|month, day, weekday, _| {
+        if day == 31 && weekday == chrono::Weekday::Sat {
+            eprintln!("Solved month {month} of 12");
+        }
+    })
+}
+
+fn print_weekday_table(table: &WeekdayTable, years: &[i32]) {
+    let impossible = table.impossible();
+    println!("**** {} impossible combinations ****", impossible.len());
+    for (month, day, weekday) in impossible {
+        // Leap years include Feb 29, so only days such as Feb 30 are missing from every calendar
+        match NaiveDate::from_ymd_opt(2020, month, day) {
+            Some(_) => println!("{month:02}-{day:02} {weekday}"),
+            None => println!("{month:02}-{day:02} {weekday} (not a date)"),
+        }
+    }
+
+    let current_year = [Local::now().year()];
+    let years = if years.is_empty() {
+        &current_year
+    } else {
+        years
+    };
+    for &year in years {
+        let dates = table.impossible_dates(year);
+        println!("**** {year} has {} impossible dates ****", dates.len());
+        for date in dates {
+            println!("{date} {}", date.weekday());
+        }
+    }
+}
+
+fn verify_db<V: Variant<N>, const N: usize>(db: &SolutionDb) -> usize {
+    let mismatches = db.verify::<V, N>();
+    for m in &mismatches {
+        println!(
+            "{} {:?}: stored {} solutions, found {}{}",
+            m.variant,
+            m.board,
+            m.stored_count,
+            m.actual_count,
+            if m.bad_solution {
+                " (stored solution is invalid)"
+            } else {
+                ""
+            }
+        );
+    }
+    mismatches.len()
+}
+
+/// Counts solutions for every day of the year, saving progress to `path` so it can be resumed
+///
+/// Counts completed by an earlier run are printed again, so the output of a resumed run
+/// is identical to the output of an uninterrupted run.
+fn count_resumable<V: Variant<N>, const N: usize>(variant: VariantOpt, path: &Path) -> Result<()> {
+    let pieces = V::pieces();
+    let mut checkpoint = if path.exists() {
+        Checkpoint::load(path, variant, &pieces)?
+    } else {
+        let date = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        Checkpoint {
+            counts: Vec::new(),
+            date,
+            solver: Solver::with_order(V::board(date), &pieces, PieceOrder::FewestPlacements),
+        }
+    };
+
+    for (date, count) in &checkpoint.counts {
+        println!(
+            "{:02}-{:02} has {} solutions",
+            date.month(),
+            date.day(),
+            count
+        );
+    }
+
+    loop {
+        let options = SolveOptions {
+            deadline: Some(Instant::now() + CHECKPOINT_INTERVAL),
+            ..Default::default()
+        };
+        checkpoint.solver.run(&options);
+
+        if checkpoint.solver.is_done() {
+            let date = checkpoint.date;
+            let count = checkpoint.solver.solution_count();
+            println!(
+                "{:02}-{:02} has {} solutions",
+                date.month(),
+                date.day(),
+                count
+            );
+            checkpoint.counts.push((date, count));
+
+            let next = date.checked_add_days(Days::new(1)).unwrap();
+            if next.year() > 2020 {
+                break;
+            }
+            checkpoint.date = next;
+            checkpoint.solver =
+                Solver::with_order(V::board(next), &pieces, PieceOrder::FewestPlacements);
+        }
+        checkpoint.save(path, variant)?;
+    }
+
+    fs::remove_file(path).with_context(|| format!("removing {}", path.display()))?;
+    Ok(())
+}
+
+/// Progress of a resumable `--all-dates` count
+struct Checkpoint<const N: usize> {
+    counts: Vec<(NaiveDate, u64)>,
+    date: NaiveDate,
+    solver: Solver<'static, N>,
+}
+
+impl<const N: usize> Checkpoint<N> {
+    fn save(&self, path: &Path, variant: VariantOpt) -> Result<()> {
+        let mut buf = Vec::new();
+        buf.extend(format!("variant {variant:?}\ndate {}\n", self.date).bytes());
+        for (date, count) in &self.counts {
+            buf.extend(format!("count {date} {count}\n").bytes());
+        }
+        self.solver.write_state(&mut buf)?;
+
+        // Write then rename, so an interruption never leaves a partially written checkpoint
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, buf).with_context(|| format!("writing {}", tmp.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("writing {}", path.display()))?;
+        Ok(())
+    }
+
+    fn load(path: &Path, variant: VariantOpt, pieces: &[Piece<'static>]) -> Result<Checkpoint<N>> {
+        let contents =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        let solver_start = contents
+            .find("solver ")
+            .context("checkpoint is missing solver state")?;
+
+        let mut date = None;
+        let mut counts = Vec::new();
+        for line in contents[..solver_start].lines() {
+            match line.split_whitespace().collect::<Vec<_>>()[..] {
+                ["variant", v] if v == format!("{variant:?}") => {}
+                ["variant", v] => bail!("checkpoint is for variant {v}, not {variant:?}"),
+                ["date", d] => date = Some(d.parse()?),
+                ["count", d, count] => counts.push((d.parse()?, count.parse()?)),
+                _ => bail!("malformed checkpoint line: {line}"),
+            }
+        }
+
+        Ok(Checkpoint {
+            counts,
+            date: date.context("checkpoint is missing date")?,
+            solver: Solver::read_state(&contents.as_bytes()[solver_start..], pieces)?,
+        })
+    }
+}
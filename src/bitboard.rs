@@ -306,6 +306,7 @@ impl BitBoard {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn has_small_gaps() {
@@ -320,4 +321,54 @@ mod tests {
         assert!(BitBoard(0xAA55AA55AA55AA55).has_small_gaps());
         assert!(BitBoard(0xFFFFFFF7FFFFFFFF).has_small_gaps());
     }
+
+    /// Reference implementation: flood-fill every empty region and look for one of size 1
+    fn has_small_gaps_flood_fill(board: BitBoard) -> bool {
+        let mut visited = board.0;
+        for start in 0..64 {
+            if visited & (1 << start) != 0 {
+                continue;
+            }
+            let mut size = 0;
+            let mut stack = vec![start];
+            visited |= 1 << start;
+            while let Some(i) = stack.pop() {
+                size += 1;
+                let (x, y) = (i % 8, i / 8);
+                let neighbors = [
+                    (x > 0).then(|| i - 1),
+                    (x < 7).then(|| i + 1),
+                    (y > 0).then(|| i - 8),
+                    (y < 7).then(|| i + 8),
+                ];
+                for n in neighbors.into_iter().flatten() {
+                    if visited & (1 << n) == 0 {
+                        visited |= 1 << n;
+                        stack.push(n);
+                    }
+                }
+            }
+            if size == 1 {
+                return true;
+            }
+        }
+        false
+    }
+
+    proptest! {
+        // `has_small_gaps` treats squares at the end of a row as neighbors of the start of the next row,
+        // which matches a flood fill as long as the last column is filled (as it is on every puzzle board)
+        #[test]
+        fn has_small_gaps_matches_flood_fill(b: u64) {
+            let board = BitBoard(b | 0x8080_8080_8080_8080);
+            prop_assert_eq!(board.has_small_gaps(), has_small_gaps_flood_fill(board));
+        }
+
+        // Sparse boards are mostly one big region, so also test dense boards that have more small regions
+        #[test]
+        fn has_small_gaps_matches_flood_fill_dense(a: u64, b: u64) {
+            let board = BitBoard(a | b | 0x8080_8080_8080_8080);
+            prop_assert_eq!(board.has_small_gaps(), has_small_gaps_flood_fill(board));
+        }
+    }
 }
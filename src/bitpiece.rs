@@ -2,6 +2,35 @@ use std::fmt;
 
 use crate::bitboard::BitBoard;
 
+/// Squares of the board's rightmost column (x = 0)
+const COLUMN: u64 = 0x0101_0101_0101_0101;
+
+/// Shifts (`y * 8 + x`) at which a piece of each width and height fits on the board, indexed by `[width - 1][height - 1]`
+///
+/// Like the edge masks of sliding piece move generation in chess, these keep shifted pieces from wrapping
+/// onto the next row or off the board.
+const ORIGINS: [[u64; 4]; 4] = {
+    let mut origins = [[0; 4]; 4];
+    let mut w = 1;
+    while w <= 4 {
+        let mut h = 1;
+        while h <= 4 {
+            let mut y = 0;
+            while y + h <= 8 {
+                let mut x = 0;
+                while x + w <= 8 {
+                    origins[w - 1][h - 1] |= 1 << (y * 8 + x);
+                    x += 1;
+                }
+                y += 1;
+            }
+            h += 1;
+        }
+        w += 1;
+    }
+    origins
+};
+
 /// Each piece fits on 4x4 bit board.
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct BitPiece(pub u16);
@@ -120,6 +149,27 @@ impl BitPiece {
         bp
     }
 
+    /// Number of squares covered by the shape
+    pub const fn cells(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Unique rotations and reflections of the shape, starting with the shape itself (aligned to the LSB)
+    pub fn orientations(self) -> Vec<BitPiece> {
+        let mut orientations = Vec::with_capacity(8);
+        let mut piece = self.align();
+        for i in 0..8 {
+            if i == 4 {
+                piece = piece.flip();
+            }
+            if !orientations.contains(&piece) {
+                orientations.push(piece);
+            }
+            piece = piece.rotate();
+        }
+        orientations
+    }
+
     #[inline]
     pub fn to_bitboard(self, x: usize, y: usize) -> BitBoard {
         let val = self.0 as u64;
@@ -129,11 +179,64 @@ impl BitPiece {
         bb |= (val & 0xF000) << 12;
         BitBoard::new(bb << (y * 8 + x))
     }
+
+    /// Every placement of the shape that doesn't intersect `occupied`, in the same order as
+    /// calling [`BitPiece::to_bitboard`] for each x and then each y
+    ///
+    /// Rather than checking each placement, this finds every shift that leaves each square of the shape
+    /// on a free square at once, by shifting the free squares under each square of the shape.
+    #[inline]
+    pub fn placements(self, occupied: BitBoard) -> Placements {
+        let shape = self.to_bitboard(0, 0).0;
+        let free = !occupied.0;
+        let mut shifts = ORIGINS[self.width() - 1][self.height() - 1];
+        let mut squares = shape;
+        while squares != 0 {
+            shifts &= free >> squares.trailing_zeros();
+            squares &= squares - 1;
+        }
+        Placements {
+            shape,
+            shifts,
+            column: shifts & COLUMN,
+            x: 0,
+        }
+    }
+}
+
+/// Iterator over placements of a shape, returned by [`BitPiece::placements`]
+#[derive(Clone, Debug)]
+pub struct Placements {
+    shape: u64,
+    /// Shifts of every remaining placement
+    shifts: u64,
+    /// Remaining shifts of column `x`
+    column: u64,
+    x: u32,
+}
+
+impl Iterator for Placements {
+    type Item = BitBoard;
+
+    #[inline]
+    fn next(&mut self) -> Option<BitBoard> {
+        while self.column == 0 {
+            if self.x == 7 {
+                return None;
+            }
+            self.x += 1;
+            self.column = self.shifts & (COLUMN << self.x);
+        }
+        let shift = self.column.trailing_zeros();
+        self.column &= self.column - 1;
+        Some(BitBoard::new(self.shape << shift))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn piece_align() {
@@ -161,6 +264,16 @@ mod tests {
         assert_eq!(BitPiece(0x1111), BitPiece(0x1111).flip());
     }
 
+    #[test]
+    fn piece_orientations() {
+        assert_eq!(BitPiece(0x0033).orientations().len(), 1);
+        assert_eq!(BitPiece(0x000F).orientations().len(), 2);
+        assert_eq!(BitPiece(0x0313).orientations().len(), 4);
+        assert_eq!(BitPiece(0x0036).orientations().len(), 4);
+        assert_eq!(BitPiece(0x001F).orientations().len(), 8);
+        assert_eq!(BitPiece(0x0017).orientations()[0], BitPiece(0x0017));
+    }
+
     #[test]
     fn piece_to_bitboard() {
         assert_eq!(BitPiece(0x23).to_bitboard(0, 0), BitBoard::new(0x0203));
@@ -176,4 +289,64 @@ mod tests {
             BitBoard::new(0xA0A0A0A000000000)
         );
     }
+
+    #[test]
+    fn piece_placements() {
+        // 2x2 square on an empty board, and with only the bottom-right 3x3 squares free
+        assert_eq!(BitPiece(0x33).placements(BitBoard::new(0)).count(), 49);
+        let placements: Vec<BitBoard> = BitPiece(0x33)
+            .placements(BitBoard::new(!0x070707))
+            .collect();
+        assert_eq!(
+            placements,
+            [0x0303, 0x030300, 0x0606, 0x060600].map(BitBoard::new)
+        );
+    }
+
+    /// Strategy for any non-empty shape, aligned to the LSB
+    fn aligned_piece() -> impl Strategy<Value = BitPiece> {
+        (1..=u16::MAX).prop_map(|b| BitPiece(b).align())
+    }
+
+    proptest! {
+        #[test]
+        fn four_rotations_are_identity(piece in aligned_piece()) {
+            prop_assert_eq!(piece.rotate().rotate().rotate().rotate(), piece);
+        }
+
+        #[test]
+        fn flip_is_involution(piece in aligned_piece()) {
+            prop_assert_eq!(piece.flip().flip(), piece);
+        }
+
+        #[test]
+        fn align_is_idempotent(b: u16) {
+            let aligned = BitPiece(b).align();
+            prop_assert_eq!(aligned.align(), aligned);
+        }
+
+        #[test]
+        fn transformations_preserve_cell_count(piece in aligned_piece(), x in 0..8usize, y in 0..8usize) {
+            let cells = piece.0.count_ones();
+            prop_assert_eq!(piece.rotate().0.count_ones(), cells);
+            prop_assert_eq!(piece.flip().0.count_ones(), cells);
+            prop_assume!(x + piece.width() <= 8 && y + piece.height() <= 8);
+            prop_assert_eq!(piece.to_bitboard(x, y).0.count_ones(), cells);
+        }
+
+        #[test]
+        fn placements_match_each_position(piece in aligned_piece(), occupied: u64) {
+            let occupied = BitBoard::new(occupied & occupied.rotate_left(17));
+            let mut expected = Vec::new();
+            for x in 0..(9 - piece.width()) {
+                for y in 0..(9 - piece.height()) {
+                    let bb = piece.to_bitboard(x, y);
+                    if !bb.intersects(occupied) {
+                        expected.push(bb);
+                    }
+                }
+            }
+            prop_assert_eq!(piece.placements(occupied).collect::<Vec<_>>(), expected);
+        }
+    }
 }
@@ -1,14 +1,17 @@
 use crate::bitboard::BitBoard;
 use crate::bitpiece::BitPiece;
 use crate::piece::Piece;
+use crate::solver::{PieceOrder, SolveOptions, SolveResult, Solver};
+use std::cmp::Reverse;
 use std::fmt;
+use std::str::FromStr;
 
 /// Board that uses N pieces for it's solution
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct Board<const N: usize> {
-    combined: BitBoard,
-    pieces: [BitBoard; N],
-    piece_count: usize,
+    pub(crate) combined: BitBoard,
+    pub(crate) pieces: [BitBoard; N],
+    pub(crate) piece_count: usize,
 }
 
 /// Soolution is just a collection of bitboards that represent where each piece is
@@ -28,69 +31,91 @@ impl<const N: usize> Board<N> {
         &self.pieces
     }
 
+    /// Places piece `index` on the board, returning an error if it is already placed or doesn't fit
     pub fn place_piece(
         &self,
+        index: usize,
         piece: BitPiece,
         x: usize,
         y: usize,
     ) -> Result<Board<N>, &'static str> {
+        if index >= N {
+            return Err("Invalid piece index");
+        }
+        if self.is_placed(index) {
+            return Err("Piece already placed");
+        }
+        if x + piece.width() > 8 || y + piece.height() > 8 {
+            return Err("Piece does not fit on board");
+        }
+
         let piece_bb = piece.to_bitboard(x, y);
         // Check if piece_bb can be placed on the board without overlap
         if piece_bb.intersects(self.combined) {
+            Err("Cannot place piece")
+        } else {
             // Create a new board that adds the piece
             let mut new_board = *self;
-            new_board.pieces[self.piece_count] = piece_bb;
+            new_board.pieces[index] = piece_bb;
             new_board.piece_count += 1;
             new_board.combined |= piece_bb;
             Ok(new_board)
-        } else {
-            Err("Cannot place piece")
         }
     }
 
+    /// Returns true if piece `index` has been placed on the board
+    pub fn is_placed(&self, index: usize) -> bool {
+        self.pieces[index] != BitBoard::new(0)
+    }
+
+    /// Finds the first solution, or every solution, in the order the original piece order finds them
     pub fn solve(&self, pieces: &[Piece], only_first: bool) -> Vec<Solution> {
-        let mut dfs = vec![*self];
-        let mut solutions = Vec::new();
-
-        while !dfs.is_empty() {
-            let board = dfs.pop().unwrap();
-            if board.is_solved() {
-                solutions.push(Solution(board.pieces.into()));
-                if only_first {
-                    break;
-                }
-            } else {
-                board.append_valid_placements(pieces[board.piece_count], &mut dfs);
-            }
+        if only_first {
+            return self.solve_with(pieces, &SolveOptions::first()).solutions;
         }
+
+        // Placing the most constrained pieces first is faster, but finds the solutions in another order
+        let options = SolveOptions {
+            piece_order: PieceOrder::FewestPlacements,
+            ..Default::default()
+        };
+        let mut solutions = self.solve_with(pieces, &options).solutions;
+        // The original order pops each piece's placements off a stack, so it tries the last
+        // variation, column, and row first
+        solutions.sort_by_cached_key(|solution| {
+            let placements: Vec<_> = pieces
+                .iter()
+                .zip(&solution.0)
+                .map(|(piece, bb)| piece.find_placement(*bb))
+                .collect();
+            Reverse(placements)
+        });
         solutions
     }
 
-    pub fn append_valid_placements(&self, piece: Piece, buf: &mut Vec<Board<N>>) {
-        assert_ne!(self.piece_count, self.pieces.len());
+    /// Solves the board, stopping early if any of the limits in `options` are reached
+    pub fn solve_with(&self, pieces: &[Piece], options: &SolveOptions) -> SolveResult {
+        Solver::with_order(*self, pieces, options.piece_order).run(options)
+    }
+
+    /// Appends a board to `buf` for every valid placement of piece `index`
+    pub fn append_valid_placements(&self, index: usize, piece: Piece, buf: &mut Vec<Board<N>>) {
+        assert!(!self.is_placed(index));
         for variation in piece.variations {
-            let w = variation.width();
-            let h = variation.height();
-            for x in 0..(9 - w) {
-                for y in 0..(9 - h) {
-                    let piece_bb = variation.to_bitboard(x, y);
-
-                    // Check if piece_bb can be placed on the board without overlap
-                    if !piece_bb.intersects(self.combined) {
-                        let new_combined = self.combined | piece_bb;
-
-                        // Disregard boards that have gaps too small to add a piece into
-                        // This significantly reduces the number of piece placements evaluated
-                        // Experimentally a 5-10x speedup from original implementation
-                        if !new_combined.has_small_gaps() {
-                            // Create a new board that adds the piece
-                            let mut new_board = *self;
-                            new_board.pieces[self.piece_count] = piece_bb;
-                            new_board.piece_count += 1;
-                            new_board.combined = new_combined;
-                            buf.push(new_board);
-                        }
-                    }
+            // Only placements without overlap
+            for piece_bb in variation.placements(self.combined) {
+                let new_combined = self.combined | piece_bb;
+
+                // Disregard boards that have gaps too small to add a piece into
+                // This significantly reduces the number of piece placements evaluated
+                // Experimentally a 5-10x speedup from original implementation
+                if !new_combined.has_small_gaps() {
+                    // Create a new board that adds the piece
+                    let mut new_board = *self;
+                    new_board.pieces[index] = piece_bb;
+                    new_board.piece_count += 1;
+                    new_board.combined = new_combined;
+                    buf.push(new_board);
                 }
             }
         }
@@ -99,6 +124,44 @@ impl<const N: usize> Board<N> {
     pub fn is_solved(&self) -> bool {
         self.piece_count == N
     }
+
+    /// Checks that a solution places each piece once (as one of its variations) and exactly fills the board
+    ///
+    /// Pieces already placed on the board must be in the same place in the solution.
+    pub fn check_solution(
+        &self,
+        pieces: &[Piece],
+        solution: &Solution,
+    ) -> Result<(), &'static str> {
+        if solution.0.len() != N || pieces.len() != N {
+            return Err("Wrong number of pieces");
+        }
+
+        let mut combined = self.combined;
+        for (i, (piece, bb)) in pieces.iter().zip(&solution.0).enumerate() {
+            if self.is_placed(i) {
+                if self.pieces[i] != *bb {
+                    return Err("Placed piece was moved");
+                }
+                continue;
+            }
+            if *bb == BitBoard::new(0) {
+                return Err("Piece is missing");
+            }
+            if piece.find_placement(*bb).is_none() {
+                return Err("Piece does not match its shape");
+            }
+            if bb.intersects(combined) {
+                return Err("Piece overlaps another piece or a covered square");
+            }
+            combined |= *bb;
+        }
+
+        if combined != BitBoard::new(!0) {
+            return Err("Solution leaves squares uncovered");
+        }
+        Ok(())
+    }
 }
 
 impl<const N: usize> fmt::Display for Board<N> {
@@ -110,7 +173,7 @@ impl<const N: usize> fmt::Display for Board<N> {
                 map[63 - x] = 'X'
             }
         }
-        for i in 0..self.piece_count {
+        for i in 0..N {
             for x in 0..64 {
                 if self.pieces[i].0 & (1u64 << x) == (1u64 << x) {
                     map[63 - x] = char::from_u32(u32::from('A') + i as u32).unwrap();
@@ -126,6 +189,41 @@ impl<const N: usize> fmt::Display for Board<N> {
     }
 }
 
+/// Parses a solution in the format it is displayed: 8 rows of 8 squares,
+/// each a piece letter or `.`/`X` for squares without a piece
+impl FromStr for Solution {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows: Vec<&str> = s.lines().filter(|line| !line.trim().is_empty()).collect();
+        if rows.len() != 8 {
+            return Err("Expected 8 rows");
+        }
+
+        let mut pieces = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            let squares: Vec<&str> = row.split_whitespace().collect();
+            if squares.len() != 8 {
+                return Err("Expected 8 squares in each row");
+            }
+            for (x, square) in squares.iter().enumerate() {
+                let index = match *square {
+                    "." | "X" => continue,
+                    letter if letter.len() == 1 && letter.as_bytes()[0].is_ascii_uppercase() => {
+                        (letter.as_bytes()[0] - b'A') as usize
+                    }
+                    _ => return Err("Invalid square"),
+                };
+                if pieces.len() <= index {
+                    pieces.resize(index + 1, BitBoard::new(0));
+                }
+                pieces[index] |= BitBoard::new(1 << (63 - (y * 8 + x)));
+            }
+        }
+        Ok(Solution(pieces))
+    }
+}
+
 impl fmt::Display for Solution {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut map = vec!['.'; 64];
@@ -145,3 +243,62 @@ impl fmt::Display for Solution {
         write!(f, "{s}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants::{DragonFjord, Tetromino, Variant};
+    use chrono::NaiveDate;
+
+    fn test_date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2020, 12, 25).unwrap()
+    }
+
+    #[test]
+    fn parse_solution() {
+        let solution = DragonFjord::solve_once(test_date()).unwrap();
+        assert_eq!(solution.to_string().parse(), Ok(solution));
+        assert!("A A .".parse::<Solution>().is_err());
+    }
+
+    #[test]
+    fn solve_in_original_order() {
+        let date = NaiveDate::from_ymd_opt(2020, 3, 1).unwrap();
+        let board = Tetromino::board(date);
+        let pieces = Tetromino::pieces();
+        let expected = Solver::with_order(board, &pieces, PieceOrder::Original)
+            .run(&SolveOptions::default())
+            .solutions;
+        assert!(expected.len() > 1);
+        assert_eq!(board.solve(&pieces, false), expected);
+    }
+
+    #[test]
+    fn check_solution() {
+        let board = DragonFjord::board(test_date());
+        let pieces = DragonFjord::pieces();
+        let solution = DragonFjord::solve_once(test_date()).unwrap();
+        assert_eq!(board.check_solution(&pieces, &solution), Ok(()));
+
+        let other_date = NaiveDate::from_ymd_opt(2020, 12, 24).unwrap();
+        assert!(DragonFjord::board(other_date)
+            .check_solution(&pieces, &solution)
+            .is_err());
+
+        let mut swapped = solution.clone();
+        swapped.0.swap(0, 1);
+        assert!(board.check_solution(&pieces, &swapped).is_err());
+
+        let mut missing = solution.clone();
+        missing.0.pop();
+        assert!(board.check_solution(&pieces, &missing).is_err());
+
+        // A piece left out of a parsed solution, e.g. with B replaced by `.`
+        let text = solution.to_string().replace('B', ".");
+        let missing_middle: Solution = text.parse().unwrap();
+        assert_eq!(
+            board.check_solution(&pieces, &missing_middle),
+            Err("Piece is missing")
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use crate::variants::{BoardDate, Variant, Weekday};
+use chrono::{Datelike, NaiveDate};
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+/// Every weekday, starting on Sunday
+pub const WEEKDAYS: [chrono::Weekday; 7] = [
+    chrono::Weekday::Sun,
+    chrono::Weekday::Mon,
+    chrono::Weekday::Tue,
+    chrono::Weekday::Wed,
+    chrono::Weekday::Thu,
+    chrono::Weekday::Fri,
+    chrono::Weekday::Sat,
+];
+
+/// Solvability of the [`Weekday`] variant for every (month, day, weekday) combination
+///
+/// The weekday of a date changes from year to year, so iterating the dates of a single year
+/// only covers one weekday per date. The table covers all 12×31×7 combinations that fit on the board
+/// (including days such as Feb 30 that never occur), and any year's calendar can be looked up in it.
+///
+/// Computing the table solves every combination, which takes several minutes,
+/// so it can be saved and loaded as text with one `MM-DD SMTWTFS` line per month and day,
+/// where each weekday (Sunday first) is `1` if solvable and `0` if impossible.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeekdayTable {
+    solvable: Vec<bool>,
+}
+
+impl WeekdayTable {
+    /// Solves every combination
+    pub fn compute() -> WeekdayTable {
+        WeekdayTable::compute_with(|_, _, _, _| {})
+    }
+
+    /// Solves every combination, calling `progress` with each result as it is found
+    pub fn compute_with(mut progress: impl FnMut(u32, u32, chrono::Weekday, bool)) -> WeekdayTable {
+        let mut solvable = vec![false; 12 * 31 * 7];
+        for month in 1..=12 {
+            for day in 1..=31 {
+                for weekday in WEEKDAYS {
+                    let result = is_solvable(month, day, weekday);
+                    solvable[index(month, day, weekday)] = result;
+                    progress(month, day, weekday, result);
+                }
+            }
+        }
+        WeekdayTable { solvable }
+    }
+
+    /// Whether the combination has a solution
+    ///
+    /// # Panics
+    ///
+    /// Panics if `month` is not within 1-12 or `day` is not within 1-31.
+    pub fn get(&self, month: u32, day: u32, weekday: chrono::Weekday) -> bool {
+        assert!((1..=12).contains(&month), "Invalid month");
+        assert!((1..=31).contains(&day), "Invalid day");
+        self.solvable[index(month, day, weekday)]
+    }
+
+    /// Whether the date (on its weekday) has a solution
+    pub fn is_solvable(&self, date: NaiveDate) -> bool {
+        self.get(date.month(), date.day(), date.weekday())
+    }
+
+    /// Every (month, day, weekday) combination without a solution
+    pub fn impossible(&self) -> Vec<(u32, u32, chrono::Weekday)> {
+        let mut impossible = Vec::new();
+        for month in 1..=12 {
+            for day in 1..=31 {
+                for weekday in WEEKDAYS {
+                    if !self.get(month, day, weekday) {
+                        impossible.push((month, day, weekday));
+                    }
+                }
+            }
+        }
+        impossible
+    }
+
+    /// Every date of the year without a solution
+    pub fn impossible_dates(&self, year: i32) -> Vec<NaiveDate> {
+        NaiveDate::from_ymd_opt(year, 1, 1)
+            .into_iter()
+            .flat_map(|first| first.iter_days())
+            .take_while(|d| d.year() == year)
+            .filter(|&d| !self.is_solvable(d))
+            .collect()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<WeekdayTable> {
+        WeekdayTable::read(io::BufReader::new(fs::File::open(path)?))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        self.write(&mut file)?;
+        file.flush()
+    }
+
+    pub fn write(&self, mut w: impl Write) -> io::Result<()> {
+        for month in 1..=12 {
+            for day in 1..=31 {
+                let row: String = WEEKDAYS
+                    .iter()
+                    .map(|&weekday| {
+                        if self.get(month, day, weekday) {
+                            '1'
+                        } else {
+                            '0'
+                        }
+                    })
+                    .collect();
+                writeln!(w, "{month:02}-{day:02} {row}")?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn read(r: impl BufRead) -> io::Result<WeekdayTable> {
+        let mut solvable = vec![None; 12 * 31 * 7];
+        for line in r.lines() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            let (date, row) = line.split_once(' ').ok_or_else(|| invalid_table(&line))?;
+            let (month, day) = date.split_once('-').ok_or_else(|| invalid_table(&line))?;
+            let month: u32 = month.parse().map_err(|_| invalid_table(&line))?;
+            let day: u32 = day.parse().map_err(|_| invalid_table(&line))?;
+            if !(1..=12).contains(&month) || !(1..=31).contains(&day) || row.len() != 7 {
+                return Err(invalid_table(&line));
+            }
+            for (weekday, c) in WEEKDAYS.into_iter().zip(row.chars()) {
+                solvable[index(month, day, weekday)] = match c {
+                    '1' => Some(true),
+                    '0' => Some(false),
+                    _ => return Err(invalid_table(&line)),
+                };
+            }
+        }
+
+        let solvable = solvable.into_iter().collect::<Option<_>>().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "Weekday table is incomplete")
+        })?;
+        Ok(WeekdayTable { solvable })
+    }
+}
+
+fn index(month: u32, day: u32, weekday: chrono::Weekday) -> usize {
+    (((month - 1) * 31 + (day - 1)) * 7 + weekday.num_days_from_sunday()) as usize
+}
+
+fn is_solvable(month: u32, day: u32, weekday: chrono::Weekday) -> bool {
+    let date = BoardDate::new(month, day).with_weekday(weekday);
+    Weekday::solve_once_at(date)
+        .expect("every month and day is on the board")
+        .is_some()
+}
+
+fn invalid_table(line: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid weekday table line: {line}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// Table where only 2020-01-01 (a Wednesday) and Sundays in February are impossible
+    fn test_table() -> WeekdayTable {
+        let mut solvable = vec![true; 12 * 31 * 7];
+        solvable[index(1, 1, chrono::Weekday::Wed)] = false;
+        for day in 1..=31 {
+            solvable[index(2, day, chrono::Weekday::Sun)] = false;
+        }
+        WeekdayTable { solvable }
+    }
+
+    #[test]
+    fn solves_combination() {
+        assert!(is_solvable(12, 1, chrono::Weekday::Tue));
+    }
+
+    #[test]
+    fn maps_years_onto_table() {
+        let table = test_table();
+        assert_eq!(table.impossible().len(), 32);
+        assert!(!table.is_solvable(ymd(2020, 1, 1)));
+        assert!(table.is_solvable(ymd(2021, 1, 1)));
+
+        // February 2026 has four Sundays, and 2026-01-01 is a Thursday
+        let dates = table.impossible_dates(2026);
+        assert_eq!(dates.len(), 4);
+        assert!(dates
+            .iter()
+            .all(|d| d.month() == 2 && d.weekday() == chrono::Weekday::Sun));
+    }
+
+    #[test]
+    fn round_trip() {
+        let table = test_table();
+        let mut buf = Vec::new();
+        table.write(&mut buf).unwrap();
+        assert!(String::from_utf8_lossy(&buf).starts_with("01-01 1110111\n"));
+        assert_eq!(WeekdayTable::read(&buf[..]).unwrap(), table);
+
+        let truncated = &buf[..buf.len() / 2];
+        assert!(WeekdayTable::read(truncated).is_err());
+        assert!(WeekdayTable::read(&b"01-01 11101\n"[..]).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+use crate::board::{Board, Solution};
+use crate::piece::Piece;
+use crate::variants::Variant;
+use chrono::NaiveDate;
+use std::fmt;
+use std::str::FromStr;
+
+/// A piece placed by its variation and position, as passed to [`Board::place_piece`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Placement {
+    pub piece: usize,
+    pub variation: usize,
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Placement {
+    /// Places the piece on `board`, returning an error if the piece or variation doesn't exist or doesn't fit
+    pub fn place<const N: usize>(
+        &self,
+        board: &Board<N>,
+        pieces: &[Piece],
+    ) -> Result<Board<N>, &'static str> {
+        let piece = pieces.get(self.piece).ok_or("Invalid piece index")?;
+        let variation = piece
+            .variations
+            .get(self.variation)
+            .ok_or("Invalid piece variation")?;
+        board.place_piece(self.piece, *variation, self.x, self.y)
+    }
+}
+
+/// A "hard mode" puzzle: a date with some pieces already placed, so that exactly one solution remains
+///
+/// Challenges are shared as text codes such as `dragon-fjord/2020-12-25/B2@4,1;E5@0,3`,
+/// where each hint is a piece letter, variation index, and (x, y) position.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Challenge {
+    pub variant: String,
+    pub date: NaiveDate,
+    pub hints: Vec<Placement>,
+}
+
+impl Challenge {
+    /// Generates the challenge for a date that needs the fewest pre-placed pieces
+    ///
+    /// Returns `None` if the date has no solutions.
+    pub fn generate<V: Variant<N>, const N: usize>(date: NaiveDate) -> Option<Challenge> {
+        let solutions = V::solve_fully(date);
+        let pieces = V::pieces();
+
+        let mut best: Option<(&Solution, Vec<usize>)> = None;
+        for target in &solutions {
+            let max_hints = best.as_ref().map_or(N, |(_, hints)| hints.len() - 1);
+            if let Some(hints) = fewest_hints(&solutions, target, max_hints) {
+                let done = hints.is_empty();
+                best = Some((target, hints));
+                if done {
+                    break;
+                }
+            }
+        }
+
+        let (target, hint_pieces) = best?;
+        let hints = hint_pieces
+            .into_iter()
+            .map(|piece| {
+                let (variation, x, y) = pieces[piece]
+                    .find_placement(target.0[piece])
+                    .expect("solution piece matches a variation");
+                Placement {
+                    piece,
+                    variation,
+                    x,
+                    y,
+                }
+            })
+            .collect();
+
+        Some(Challenge {
+            variant: V::NAME.to_owned(),
+            date,
+            hints,
+        })
+    }
+
+    /// Builds the date's board with the hints already placed
+    pub fn board<V: Variant<N>, const N: usize>(&self) -> Result<Board<N>, &'static str> {
+        if self.variant != V::NAME {
+            return Err("Challenge is for a different variant");
+        }
+        let pieces = V::pieces();
+        self.hints
+            .iter()
+            .try_fold(V::board(self.date), |board, hint| {
+                hint.place(&board, &pieces)
+            })
+    }
+
+    /// Finds the challenge's solution, returning an error unless there is exactly one
+    pub fn solve<V: Variant<N>, const N: usize>(&self) -> Result<Solution, &'static str> {
+        let mut solutions = self.board::<V, N>()?.solve(&V::pieces(), false);
+        match solutions.len() {
+            1 => Ok(solutions.remove(0)),
+            0 => Err("Challenge has no solution"),
+            _ => Err("Challenge has more than one solution"),
+        }
+    }
+}
+
+/// Finds the smallest set of pieces (at most `max_hints`) whose placements only appear in `target`
+fn fewest_hints(solutions: &[Solution], target: &Solution, max_hints: usize) -> Option<Vec<usize>> {
+    let n = target.0.len();
+    for hint_count in 0..=max_hints.min(n) {
+        for mask in 0u32..(1 << n) {
+            if mask.count_ones() as usize != hint_count {
+                continue;
+            }
+            let hints: Vec<usize> = (0..n).filter(|i| mask & (1 << i) != 0).collect();
+            let matching = solutions
+                .iter()
+                .filter(|s| hints.iter().all(|&i| s.0[i] == target.0[i]))
+                .take(2)
+                .count();
+            if matching == 1 {
+                return Some(hints);
+            }
+        }
+    }
+    None
+}
+
+impl fmt::Display for Challenge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hints: Vec<String> = self
+            .hints
+            .iter()
+            .map(|h| {
+                let letter = char::from_u32(u32::from('A') + h.piece as u32).unwrap();
+                format!("{letter}{}@{},{}", h.variation, h.x, h.y)
+            })
+            .collect();
+        write!(f, "{}/{}/{}", self.variant, self.date, hints.join(";"))
+    }
+}
+
+impl FromStr for Challenge {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().splitn(3, '/');
+        let (Some(variant), Some(date), Some(hints)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err("Expected variant/date/hints");
+        };
+        let date = date.parse().map_err(|_| "Invalid date")?;
+        let hints = hints
+            .split(';')
+            .filter(|h| !h.is_empty())
+            .map(parse_hint)
+            .collect::<Result<_, _>>()?;
+        Ok(Challenge {
+            variant: variant.to_owned(),
+            date,
+            hints,
+        })
+    }
+}
+
+/// Parses a hint formatted as `<letter><variation>@<x>,<y>`
+fn parse_hint(s: &str) -> Result<Placement, &'static str> {
+    let mut chars = s.chars();
+    let letter = chars
+        .next()
+        .filter(char::is_ascii_uppercase)
+        .ok_or("Invalid piece letter")?;
+    let (variation, position) = chars.as_str().split_once('@').ok_or("Expected @ in hint")?;
+    let (x, y) = position.split_once(',').ok_or("Expected x,y in hint")?;
+    Ok(Placement {
+        piece: (letter as u8 - b'A') as usize,
+        variation: variation.parse().map_err(|_| "Invalid variation")?,
+        x: x.parse().map_err(|_| "Invalid x position")?,
+        y: y.parse().map_err(|_| "Invalid y position")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants::{CreaMakerspace, DragonFjord};
+
+    fn test_date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2020, 12, 25).unwrap()
+    }
+
+    fn test_challenge() -> Challenge {
+        Challenge::generate::<DragonFjord, 8>(test_date()).expect("date has solutions")
+    }
+
+    #[test]
+    fn generated_challenge_is_unique() {
+        let challenge = test_challenge();
+        assert!(!challenge.hints.is_empty());
+        let solution = challenge.solve::<DragonFjord, 8>().unwrap();
+        assert!(DragonFjord::solve_fully(test_date()).contains(&solution));
+    }
+
+    #[test]
+    fn share_code_round_trip() {
+        let challenge = test_challenge();
+        let code = challenge.to_string();
+        assert!(code.starts_with("dragon-fjord/2020-12-25/"));
+        assert_eq!(code.parse::<Challenge>(), Ok(challenge));
+
+        let parsed: Challenge = "dragon-fjord/2020-12-25/".parse().unwrap();
+        assert!(parsed.hints.is_empty());
+        assert!("dragon-fjord/2020-12-25/B2@4".parse::<Challenge>().is_err());
+        assert!("dragon-fjord/12-25/".parse::<Challenge>().is_err());
+    }
+
+    #[test]
+    fn rejects_other_variant() {
+        let challenge = test_challenge();
+        assert!(challenge.board::<CreaMakerspace, 8>().is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use chrono::{Datelike, Days, Month, NaiveDate, Weekday};
+use std::error::Error;
+use std::fmt;
+
+/// Error returned by [`parse_date`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DateParseError {
+    /// The input does not match any supported format
+    Unrecognized(String),
+    /// The input matches a format, but the date does not exist (e.g. `feb 30`)
+    InvalidDate(String),
+}
+
+impl fmt::Display for DateParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DateParseError::Unrecognized(s) => write!(
+                f,
+                "unrecognized date '{s}' (expected Y-M-D, M-D, today, tomorrow, yesterday, +N, -N, \
+                 [next|last] <weekday>, <month> <day> [year], or an ISO week date such as 2026-W52-5)"
+            ),
+            DateParseError::InvalidDate(s) => write!(f, "'{s}' is not a valid date"),
+        }
+    }
+}
+
+impl Error for DateParseError {}
+
+/// Parses a date, relative to `today` where needed
+///
+/// Supported formats (case-insensitive):
+/// - `2026-12-25` or `12-25` (in the current year), or with slashes (`2026/12/25` or `12/25`)
+/// - `today`, `tomorrow`, or `yesterday`
+/// - `+3` or `-3` days from today
+/// - `friday` (today or the next Friday), `next friday` (after today), or `last friday` (before today)
+/// - `dec 25`, `25 december`, or `dec 25 2027`
+/// - ISO week dates such as `2026-W52-5` or `2026-W52` (Monday)
+pub fn parse_date(s: &str, today: NaiveDate) -> Result<NaiveDate, DateParseError> {
+    let input = s.trim().to_ascii_lowercase();
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let unrecognized = || DateParseError::Unrecognized(s.trim().to_owned());
+    let invalid = || DateParseError::InvalidDate(s.trim().to_owned());
+
+    match words[..] {
+        ["today"] => Ok(today),
+        ["tomorrow"] => today.succ_opt().ok_or_else(invalid),
+        ["yesterday"] => today.pred_opt().ok_or_else(invalid),
+        [offset] if offset.starts_with(['+', '-']) => {
+            let days: i64 = offset.parse().map_err(|_| unrecognized())?;
+            let shifted = match days {
+                0.. => today.checked_add_days(Days::new(days as u64)),
+                _ => today.checked_sub_days(Days::new(days.unsigned_abs())),
+            };
+            shifted.ok_or_else(invalid)
+        }
+        [weekday] if weekday.parse::<Weekday>().is_ok() => {
+            Ok(next_weekday(today, weekday.parse().unwrap(), 0))
+        }
+        ["next", weekday] => {
+            let weekday = weekday.parse().map_err(|_| unrecognized())?;
+            Ok(next_weekday(today, weekday, 1))
+        }
+        ["last", weekday] => {
+            let weekday: Weekday = weekday.parse().map_err(|_| unrecognized())?;
+            let days_back =
+                (7 + today.weekday().num_days_from_monday() - weekday.num_days_from_monday() - 1)
+                    % 7
+                    + 1;
+            Ok(today - Days::new(days_back.into()))
+        }
+        [a, b] | [a, b, _] if month_day(a, b).is_some() => {
+            let (month, day) = month_day(a, b).unwrap();
+            let year = match words.get(2) {
+                Some(year) => year.parse().map_err(|_| unrecognized())?,
+                None => today.year(),
+            };
+            NaiveDate::from_ymd_opt(year, month, day).ok_or_else(invalid)
+        }
+        [date] if date.contains("-w") => parse_iso_week(date)
+            .ok_or_else(unrecognized)?
+            .ok_or_else(invalid),
+        [date] => parse_numeric(date, today.year())
+            .ok_or_else(unrecognized)?
+            .ok_or_else(invalid),
+        _ => Err(unrecognized()),
+    }
+}
+
+/// The first `weekday` at least `min_days` after `today`
+fn next_weekday(today: NaiveDate, weekday: Weekday, min_days: u32) -> NaiveDate {
+    let from = today + Days::new(min_days.into());
+    let ahead = (7 + weekday.num_days_from_monday() - from.weekday().num_days_from_monday()) % 7;
+    from + Days::new(ahead.into())
+}
+
+/// Parses `<month> <day>` or `<day> <month>`, where the month is a name or abbreviation
+fn month_day(a: &str, b: &str) -> Option<(u32, u32)> {
+    let (month, day) = match (a.parse::<Month>(), b.parse::<Month>()) {
+        (Ok(month), Err(_)) => (month, b),
+        (Err(_), Ok(month)) => (month, a),
+        _ => return None,
+    };
+    Some((month.number_from_month(), day.parse().ok()?))
+}
+
+/// Parses `YYYY-Www-D` or `YYYY-Www`, returning `Some(None)` if the week or day doesn't exist
+fn parse_iso_week(s: &str) -> Option<Option<NaiveDate>> {
+    let (year, rest) = s.split_once("-w")?;
+    let (week, day) = rest.split_once('-').unwrap_or((rest, "1"));
+    let year = year.parse().ok()?;
+    let week = week.parse().ok()?;
+    let weekday = match day.parse::<u8>().ok()? {
+        day @ 1..=7 => (1..day).fold(Weekday::Mon, |d, _| d.succ()),
+        _ => return Some(None),
+    };
+    Some(NaiveDate::from_isoywd_opt(year, week, weekday))
+}
+
+/// Parses `Y-M-D` or `M-D` (with `-` or `/` separators), returning `Some(None)` if the month or day doesn't exist
+fn parse_numeric(s: &str, year: i32) -> Option<Option<NaiveDate>> {
+    let parts = s
+        .split(['-', '/'])
+        .map(|part| part.parse::<u32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    match parts[..] {
+        [y, m, d] => Some(NaiveDate::from_ymd_opt(i32::try_from(y).ok()?, m, d)),
+        [m, d] => Some(NaiveDate::from_ymd_opt(year, m, d)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// A Sunday
+    fn today() -> NaiveDate {
+        ymd(2026, 10, 18)
+    }
+
+    fn parse(s: &str) -> Result<NaiveDate, DateParseError> {
+        parse_date(s, today())
+    }
+
+    #[test]
+    fn numeric_dates() {
+        assert_eq!(parse("2027-01-02"), Ok(ymd(2027, 1, 2)));
+        assert_eq!(parse("12-25"), Ok(ymd(2026, 12, 25)));
+        assert_eq!(parse("2027/01/02"), Ok(ymd(2027, 1, 2)));
+        assert_eq!(parse("12/25"), Ok(ymd(2026, 12, 25)));
+        assert_eq!(
+            parse("2-29"),
+            Err(DateParseError::InvalidDate("2-29".into()))
+        );
+        assert_eq!(
+            parse("1-2-3-4"),
+            Err(DateParseError::Unrecognized("1-2-3-4".into()))
+        );
+    }
+
+    #[test]
+    fn relative_dates() {
+        assert_eq!(parse("Today"), Ok(today()));
+        assert_eq!(parse("tomorrow"), Ok(ymd(2026, 10, 19)));
+        assert_eq!(parse("yesterday"), Ok(ymd(2026, 10, 17)));
+        assert_eq!(parse("+3"), Ok(ymd(2026, 10, 21)));
+        assert_eq!(parse("-18"), Ok(ymd(2026, 9, 30)));
+        assert_eq!(parse("+0"), Ok(today()));
+        assert!(matches!(parse("+x"), Err(DateParseError::Unrecognized(_))));
+    }
+
+    #[test]
+    fn weekdays() {
+        assert_eq!(parse("sunday"), Ok(today()));
+        assert_eq!(parse("next sunday"), Ok(ymd(2026, 10, 25)));
+        assert_eq!(parse("last sunday"), Ok(ymd(2026, 10, 11)));
+        assert_eq!(parse("Fri"), Ok(ymd(2026, 10, 23)));
+        assert_eq!(parse("next friday"), Ok(ymd(2026, 10, 23)));
+        assert_eq!(parse("last friday"), Ok(ymd(2026, 10, 16)));
+        assert_eq!(parse("next monday"), Ok(ymd(2026, 10, 19)));
+        assert!(matches!(
+            parse("next week"),
+            Err(DateParseError::Unrecognized(_))
+        ));
+    }
+
+    #[test]
+    fn month_names() {
+        assert_eq!(parse("dec 25"), Ok(ymd(2026, 12, 25)));
+        assert_eq!(parse("25 December"), Ok(ymd(2026, 12, 25)));
+        assert_eq!(parse("feb 29 2028"), Ok(ymd(2028, 2, 29)));
+        assert_eq!(
+            parse("feb 30"),
+            Err(DateParseError::InvalidDate("feb 30".into()))
+        );
+        assert!(matches!(
+            parse("dec twenty"),
+            Err(DateParseError::Unrecognized(_))
+        ));
+    }
+
+    #[test]
+    fn iso_weeks() {
+        assert_eq!(parse("2026-W52-5"), Ok(ymd(2026, 12, 25)));
+        assert_eq!(parse("2026-w01"), Ok(ymd(2025, 12, 29)));
+        assert_eq!(
+            parse("2026-W54-1"),
+            Err(DateParseError::InvalidDate("2026-W54-1".into()))
+        );
+        assert_eq!(
+            parse("2026-W01-8"),
+            Err(DateParseError::InvalidDate("2026-W01-8".into()))
+        );
+    }
+}
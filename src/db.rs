@@ -0,0 +1,413 @@
+use crate::bitboard::BitBoard;
+use crate::board::{Board, Solution};
+use crate::piece::Piece;
+use crate::variants::{Variant, VariantId};
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::sync::OnceLock;
+
+const MAGIC: &[u8; 4] = b"TPDB";
+const VERSION: u8 = 1;
+
+static GLOBAL: OnceLock<SolutionDb> = OnceLock::new();
+
+/// Precomputed solutions for each variant and date
+///
+/// Entries are keyed by variant name and the starting board, so dates that share a board
+/// (e.g. the same month and day in different years) share an entry.
+/// Each entry stores the solution count and either the first solution or every solution.
+///
+/// Once installed with [`SolutionDb::install`], [`Variant::solve_once`] and [`Variant::solve_fully`]
+/// answer from the database whenever it has an entry for the requested date.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolutionDb {
+    records: HashMap<(String, u64), Record>,
+}
+
+/// Solutions stored for a single date
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub count: u64,
+    /// Either the first solution or all solutions (see [`Entry::is_complete`])
+    pub solutions: Vec<Solution>,
+}
+
+impl Entry {
+    /// Returns true if the entry includes every solution
+    pub fn is_complete(&self) -> bool {
+        self.solutions.len() as u64 == self.count
+    }
+}
+
+/// Difference between a database entry and a recomputed solve
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    pub variant: &'static str,
+    /// Starting board of the mismatched entry
+    pub board: BitBoard,
+    pub stored_count: u64,
+    pub actual_count: u64,
+    /// True if any stored solution is missing from the recomputed solutions
+    pub bad_solution: bool,
+}
+
+/// Entry as stored on disk, with each piece encoded as a variation index and offset
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Record {
+    count: u64,
+    piece_count: u8,
+    placements: Vec<u16>,
+}
+
+impl SolutionDb {
+    pub fn new() -> SolutionDb {
+        SolutionDb::default()
+    }
+
+    /// Installs this database for use by [`Variant::solve_once`] and [`Variant::solve_fully`]
+    ///
+    /// Returns the database back as an error if one was already installed.
+    pub fn install(self) -> Result<(), SolutionDb> {
+        GLOBAL.set(self)
+    }
+
+    /// The database installed with [`SolutionDb::install`], if any
+    pub fn global() -> Option<&'static SolutionDb> {
+        GLOBAL.get()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn contains<V: Variant<N>, const N: usize>(&self, date: NaiveDate) -> bool {
+        self.contains_board::<V, N>(V::board(date))
+    }
+
+    pub fn contains_board<V: Variant<N>, const N: usize>(&self, board: Board<N>) -> bool {
+        self.records.contains_key(&key::<V, N>(board))
+    }
+
+    /// Solves the given date and stores the result
+    ///
+    /// Stores every solution if `full` is true, otherwise only the first solution (and the count).
+    pub fn record<V: Variant<N>, const N: usize>(&mut self, date: NaiveDate, full: bool) {
+        self.record_board::<V, N>(V::board(date), full)
+    }
+
+    /// Solves the given starting board and stores the result, like [`SolutionDb::record`]
+    pub fn record_board<V: Variant<N>, const N: usize>(&mut self, board: Board<N>, full: bool) {
+        let pieces = V::pieces();
+        let solutions = board.solve(&pieces, false);
+        let stored = if full {
+            &solutions[..]
+        } else {
+            &solutions[..solutions.len().min(1)]
+        };
+
+        let record = Record {
+            count: solutions.len() as u64,
+            piece_count: N as u8,
+            placements: stored
+                .iter()
+                .flat_map(|solution| encode_solution(solution, &pieces))
+                .collect(),
+        };
+        self.records.insert(key::<V, N>(board), record);
+    }
+
+    /// Looks up the stored solutions for the given date
+    pub fn get<V: Variant<N>, const N: usize>(&self, date: NaiveDate) -> Option<Entry> {
+        self.get_board::<V, N>(V::board(date))
+    }
+
+    /// Looks up the stored solutions for the given starting board
+    pub fn get_board<V: Variant<N>, const N: usize>(&self, board: Board<N>) -> Option<Entry> {
+        let record = self.records.get(&key::<V, N>(board))?;
+        Some(decode_record(record, &V::pieces()))
+    }
+
+    /// Recomputes every entry for the variant, returning any that disagree with the database
+    pub fn verify<V: Variant<N>, const N: usize>(&self) -> Vec<Mismatch> {
+        let pieces = V::pieces();
+        let mut mismatches = Vec::new();
+        for ((name, board), record) in &self.records {
+            if name != V::NAME {
+                continue;
+            }
+            let entry = decode_record(record, &pieces);
+            let actual = Board::<N>::new(BitBoard(*board), BitBoard(!0)).solve(&pieces, false);
+            let bad_solution = entry.solutions.iter().any(|s| !actual.contains(s));
+            if bad_solution || entry.count != actual.len() as u64 {
+                mismatches.push(Mismatch {
+                    variant: V::NAME,
+                    board: BitBoard(*board),
+                    stored_count: entry.count,
+                    actual_count: actual.len() as u64,
+                    bad_solution,
+                });
+            }
+        }
+        mismatches.sort_by_key(|m| m.board.0);
+        mismatches
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<SolutionDb> {
+        SolutionDb::read(fs::File::open(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut buf = Vec::new();
+        self.write(&mut buf)?;
+        fs::write(path, buf)
+    }
+
+    /// Writes the database in its compact binary format
+    pub fn write<W: Write>(&self, mut w: W) -> io::Result<()> {
+        // Sort records so the same database always produces the same file
+        let mut records: Vec<_> = self.records.iter().collect();
+        records.sort_by(|a, b| a.0.cmp(b.0));
+
+        w.write_all(MAGIC)?;
+        w.write_all(&[VERSION])?;
+        w.write_all(&(records.len() as u32).to_le_bytes())?;
+        for ((name, board), record) in records {
+            w.write_all(&[name.len() as u8])?;
+            w.write_all(name.as_bytes())?;
+            w.write_all(&board.to_le_bytes())?;
+            w.write_all(&record.count.to_le_bytes())?;
+            w.write_all(&[record.piece_count])?;
+            w.write_all(&(record.placements.len() as u32).to_le_bytes())?;
+            for placement in &record.placements {
+                w.write_all(&placement.to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a database written by [`SolutionDb::write`]
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] if any entry is not made of whole solutions of its variant's pieces.
+    pub fn read<R: Read>(r: R) -> io::Result<SolutionDb> {
+        let mut r = io::BufReader::new(r);
+        let mut magic = [0; 4];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC || read_u8(&mut r)? != VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a solution database",
+            ));
+        }
+
+        let mut records = HashMap::new();
+        for _ in 0..read_u32(&mut r)? {
+            let mut name = vec![0; read_u8(&mut r)? as usize];
+            r.read_exact(&mut name)?;
+            let name = String::from_utf8(name)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            let board = read_u64(&mut r)?;
+            let count = read_u64(&mut r)?;
+            let piece_count = read_u8(&mut r)?;
+            let placements = (0..read_u32(&mut r)?)
+                .map(|_| read_u16(&mut r))
+                .collect::<io::Result<_>>()?;
+            let record = Record {
+                count,
+                piece_count,
+                placements,
+            };
+            check_record(&name, &record)?;
+            records.insert((name, board), record);
+        }
+        Ok(SolutionDb { records })
+    }
+}
+
+fn key<V: Variant<N>, const N: usize>(board: Board<N>) -> (String, u64) {
+    (V::NAME.to_owned(), board.combined.0)
+}
+
+/// Encodes each piece of the solution as `variation << 8 | offset`
+fn encode_solution<'a>(
+    solution: &'a Solution,
+    pieces: &'a [Piece],
+) -> impl Iterator<Item = u16> + 'a {
+    solution.0.iter().zip(pieces).map(|(bb, piece)| {
+        let (v, x, y) = piece
+            .find_placement(*bb)
+            .expect("solution piece matches a variation");
+        ((v as u16) << 8) | (y * 8 + x) as u16
+    })
+}
+
+/// Checks that a record read from disk decodes into whole solutions of its variant's pieces
+fn check_record(name: &str, record: &Record) -> io::Result<()> {
+    let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+    let variant: VariantId = name.parse().map_err(invalid)?;
+    let pieces = variant.pieces();
+    if record.piece_count as usize != pieces.len()
+        || !record.placements.len().is_multiple_of(pieces.len())
+    {
+        return Err(invalid("piece count does not match the variant"));
+    }
+    for (placement, piece) in record.placements.iter().zip(pieces.iter().cycle()) {
+        let offset = (placement & 0xFF) as usize;
+        let (x, y) = (offset % 8, offset / 8);
+        match piece.variations.get((placement >> 8) as usize) {
+            Some(v) if x + v.width() <= 8 && y + v.height() <= 8 => {}
+            _ => return Err(invalid("invalid piece placement")),
+        }
+    }
+    Ok(())
+}
+
+fn decode_record(record: &Record, pieces: &[Piece]) -> Entry {
+    let solutions = record
+        .placements
+        .chunks(record.piece_count.max(1) as usize)
+        .map(|chunk| {
+            Solution(
+                chunk
+                    .iter()
+                    .zip(pieces)
+                    .map(|(placement, piece)| {
+                        let offset = (placement & 0xFF) as usize;
+                        piece.variations[(placement >> 8) as usize]
+                            .to_bitboard(offset % 8, offset / 8)
+                    })
+                    .collect(),
+            )
+        })
+        .collect();
+    Entry {
+        count: record.count,
+        solutions,
+    }
+}
+
+fn read_u8(r: &mut impl Read) -> io::Result<u8> {
+    let mut buf = [0; 1];
+    r.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_u16(r: &mut impl Read) -> io::Result<u16> {
+    let mut buf = [0; 2];
+    r.read_exact(&mut buf)?;
+    Ok(u16::from_le_bytes(buf))
+}
+
+fn read_u32(r: &mut impl Read) -> io::Result<u32> {
+    let mut buf = [0; 4];
+    r.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64(r: &mut impl Read) -> io::Result<u64> {
+    let mut buf = [0; 8];
+    r.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants::{BoardDate, DragonFjord, Tetromino};
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut db = SolutionDb::new();
+        db.record::<DragonFjord, 8>(ymd(2020, 12, 25), true);
+        db.record::<Tetromino, 9>(ymd(2020, 3, 1), false);
+
+        let mut buf = Vec::new();
+        db.write(&mut buf).unwrap();
+        let db = SolutionDb::read(&buf[..]).unwrap();
+
+        let entry = db.get::<DragonFjord, 8>(ymd(2020, 12, 25)).unwrap();
+        assert!(entry.is_complete());
+        assert_eq!(entry.solutions, DragonFjord::solve_fully(ymd(2020, 12, 25)));
+
+        // Same board in a different year
+        let entry = db.get::<Tetromino, 9>(ymd(2023, 3, 1)).unwrap();
+        assert_eq!(entry.solutions.len(), 1);
+        assert_eq!(
+            entry.count,
+            Tetromino::solve_fully(ymd(2020, 3, 1)).len() as u64
+        );
+
+        assert!(db.get::<DragonFjord, 8>(ymd(2020, 3, 1)).is_none());
+        assert!(db.verify::<DragonFjord, 8>().is_empty());
+    }
+
+    #[test]
+    fn record_board_date() {
+        // Dates that never occur, like Apr 31, are on the board too
+        let board = DragonFjord::board_at(BoardDate::new(4, 31)).unwrap();
+        let mut db = SolutionDb::new();
+        assert!(!db.contains_board::<DragonFjord, 8>(board));
+        db.record_board::<DragonFjord, 8>(board, false);
+        assert!(db.contains_board::<DragonFjord, 8>(board));
+        assert_eq!(
+            db.get_board::<DragonFjord, 8>(board).unwrap().count,
+            DragonFjord::count_at(BoardDate::new(4, 31)).unwrap() as u64
+        );
+    }
+
+    #[test]
+    fn verify_detects_mismatch() {
+        let mut db = SolutionDb::new();
+        db.record::<DragonFjord, 8>(ymd(2020, 1, 1), false);
+        for record in db.records.values_mut() {
+            record.count += 1;
+        }
+        let mismatches = db.verify::<DragonFjord, 8>();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].stored_count, mismatches[0].actual_count + 1);
+    }
+
+    #[test]
+    fn read_rejects_corrupt_records() {
+        let mut db = SolutionDb::new();
+        db.record::<DragonFjord, 8>(ymd(2020, 12, 25), false);
+        let corrupt = |change: fn(&mut Record)| {
+            let mut db = db.clone();
+            db.records.values_mut().for_each(change);
+            let mut buf = Vec::new();
+            db.write(&mut buf).unwrap();
+            SolutionDb::read(&buf[..]).unwrap_err().kind()
+        };
+
+        // Variation that doesn't exist, placement off the board, and piece counts of another variant
+        assert_eq!(
+            corrupt(|r| r.placements[0] |= 0x7F00),
+            io::ErrorKind::InvalidData
+        );
+        assert_eq!(
+            corrupt(|r| r.placements[0] = 0x3F),
+            io::ErrorKind::InvalidData
+        );
+        assert_eq!(corrupt(|r| r.piece_count = 7), io::ErrorKind::InvalidData);
+        assert_eq!(
+            corrupt(|r| r.placements.truncate(7)),
+            io::ErrorKind::InvalidData
+        );
+
+        // Truncated file
+        let mut buf = Vec::new();
+        db.write(&mut buf).unwrap();
+        buf.truncate(buf.len() - 1);
+        assert!(SolutionDb::read(&buf[..]).is_err());
+    }
+}
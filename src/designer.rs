@@ -0,0 +1,187 @@
+use crate::bitboard::BitBoard;
+use crate::bitpiece::BitPiece;
+use crate::board::Board;
+use crate::piece::Piece;
+use crate::variants::Variant;
+use chrono::{Datelike, Days, NaiveDate};
+
+/// Searches a pool of candidate shapes for piece sets that solve every date on a board
+///
+/// A piece set is only considered if its total area exactly fills the board's open squares,
+/// so every date must leave the same number of squares open.
+#[derive(Clone, Debug)]
+pub struct Designer {
+    boards: Vec<BitBoard>,
+    pool: Vec<BitPiece>,
+    orientations: Vec<Vec<BitPiece>>,
+}
+
+/// Piece set that solves every date
+#[derive(Clone, Debug, PartialEq)]
+pub struct Design {
+    /// Indices of the chosen pieces within the pool
+    pub pieces: Vec<usize>,
+    /// Fewest solutions for any date
+    pub min_solutions: usize,
+    /// Average number of solutions per date
+    pub mean_solutions: f64,
+}
+
+impl Designer {
+    /// Creates a designer from the starting board for each date
+    ///
+    /// Each board has every square filled except the squares that pieces must cover.
+    pub fn new(boards: Vec<BitBoard>, pool: Vec<BitPiece>) -> Designer {
+        assert!(!boards.is_empty(), "designer requires at least one board");
+        let orientations = pool.iter().map(|piece| piece.orientations()).collect();
+        Designer {
+            boards,
+            pool,
+            orientations,
+        }
+    }
+
+    /// Creates a designer for the board layout of a variant, using every date of 2020
+    pub fn for_variant<V: Variant<N>, const N: usize>(pool: Vec<BitPiece>) -> Designer {
+        let mut boards = Vec::new();
+        let mut d = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        while d.year() < 2021 {
+            let board = V::board(d).combined;
+            if !boards.contains(&board) {
+                boards.push(board);
+            }
+            d = d.checked_add_days(Days::new(1)).unwrap();
+        }
+        Designer::new(boards, pool)
+    }
+
+    pub fn pool(&self) -> &[BitPiece] {
+        &self.pool
+    }
+
+    /// Number of squares a piece set must cover
+    pub fn area(&self) -> u32 {
+        self.boards[0].0.count_zeros()
+    }
+
+    /// Finds sets of `N` pieces that solve every date, ranked by their fewest solutions for any date
+    ///
+    /// Ranking requires counting every solution for every date, which is slow,
+    /// so `limit` stops the search after that many sets are found.
+    pub fn search<const N: usize>(&self, limit: Option<usize>) -> Vec<Design> {
+        let mut designs = Vec::new();
+        // Dates that have failed recently are tried first, since they tend to rule out other sets too
+        let mut date_order: Vec<usize> = (0..self.boards.len()).collect();
+        let mut chosen = Vec::with_capacity(N);
+        self.search_from::<N>(0, &mut chosen, &mut date_order, &mut designs, limit);
+
+        designs.sort_by(|a, b| {
+            b.min_solutions
+                .cmp(&a.min_solutions)
+                .then(b.mean_solutions.total_cmp(&a.mean_solutions))
+        });
+        designs
+    }
+
+    /// Recursively extends `chosen` with pool indices from `start` onward
+    ///
+    /// Returns false once `limit` designs have been found.
+    fn search_from<const N: usize>(
+        &self,
+        start: usize,
+        chosen: &mut Vec<usize>,
+        date_order: &mut [usize],
+        designs: &mut Vec<Design>,
+        limit: Option<usize>,
+    ) -> bool {
+        let area: u32 = chosen.iter().map(|&i| self.pool[i].cells()).sum();
+        if chosen.len() == N {
+            if area == self.area() {
+                if let Some(design) = self.evaluate::<N>(chosen, date_order) {
+                    designs.push(design);
+                }
+            }
+            return !matches!(limit, Some(limit) if designs.len() >= limit);
+        }
+
+        for i in start..self.pool.len() {
+            if area + self.pool[i].cells() > self.area() {
+                continue;
+            }
+            chosen.push(i);
+            let more = self.search_from::<N>(i + 1, chosen, date_order, designs, limit);
+            chosen.pop();
+            if !more {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Counts solutions for every date, or returns `None` if any date is unsolvable
+    fn evaluate<const N: usize>(
+        &self,
+        chosen: &[usize],
+        date_order: &mut [usize],
+    ) -> Option<Design> {
+        let pieces: Vec<Piece> = chosen
+            .iter()
+            .map(|&i| Piece {
+                variations: &self.orientations[i],
+            })
+            .collect();
+
+        for i in 0..date_order.len() {
+            let board = Board::<N>::new(self.boards[date_order[i]], BitBoard(!0));
+            if board.solve(&pieces, true).is_empty() {
+                date_order[..=i].rotate_right(1);
+                return None;
+            }
+        }
+
+        let counts: Vec<usize> = self
+            .boards
+            .iter()
+            .map(|&board| {
+                Board::<N>::new(board, BitBoard(!0))
+                    .solve(&pieces, false)
+                    .len()
+            })
+            .collect();
+        Some(Design {
+            pieces: chosen.to_vec(),
+            min_solutions: counts.iter().copied().min().unwrap_or(0),
+            mean_solutions: counts.iter().sum::<usize>() as f64 / counts.len() as f64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::*;
+    use crate::variants::DragonFjord;
+
+    #[test]
+    fn finds_dragon_fjord_pieces() {
+        let boards = [(1, 1), (12, 25)]
+            .iter()
+            .map(|&(m, d)| {
+                DragonFjord::board(NaiveDate::from_ymd_opt(2020, m, d).unwrap()).combined
+            })
+            .collect();
+        // The DragonFjord pieces, plus a square that is never needed
+        let mut pool: Vec<BitPiece> = DragonFjord::pieces()
+            .iter()
+            .map(|piece| piece.variations[0])
+            .collect();
+        pool.push(PIECE_SQUARE.0[0]);
+
+        let designer = Designer::new(boards, pool);
+        assert_eq!(designer.area(), 41);
+        let designs = designer.search::<8>(None);
+        assert_eq!(designs.len(), 1);
+        assert_eq!(designs[0].pieces, (0..8).collect::<Vec<_>>());
+        assert!(designs[0].min_solutions > 0);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::board::Solution;
+use crate::db::SolutionDb;
+use crate::emoji;
+use crate::variants::{
+    CreaMakerspace, DragonFjord, JarringWords, Tetromino, Variant, VariantId, Weekday,
+};
+use chrono::{Datelike, NaiveDate};
+use std::fmt::Write;
+
+/// Shown when a difficulty is missing, since ranks are only read from a database
+const RANK_NOTE: &str =
+    "Difficulty needs a solution database with every date of the year (see `build-db`)";
+
+/// Summary of a date's puzzle for every variant, e.g. to post in a team chat
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Digest {
+    pub date: NaiveDate,
+    pub entries: Vec<DigestEntry>,
+}
+
+/// A variant's solutions for the digest's date
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DigestEntry {
+    pub variant: VariantId,
+    pub count: usize,
+    /// The first solution, if there is one
+    pub solution: Option<Solution>,
+    /// The first solution rendered with [`emoji::render`]
+    pub grid: Option<String>,
+    /// Rank of the date's solution count within its year (1 has the fewest solutions, so is the hardest),
+    /// and the number of dates ranked
+    ///
+    /// Only available when the installed [`SolutionDb`] has every date of the year,
+    /// since counting every date on the fly can take hours.
+    pub rank: Option<(usize, usize)>,
+}
+
+impl Digest {
+    /// Solves the date for every variant, using the installed [`SolutionDb`] where it has the date
+    pub fn new(date: NaiveDate) -> Digest {
+        Digest::for_variants(date, &VariantId::ALL)
+    }
+
+    /// Like [`Digest::new`], but only for some variants
+    pub fn for_variants(date: NaiveDate, variants: &[VariantId]) -> Digest {
+        let entries = variants
+            .iter()
+            .map(|&variant| match variant {
+                VariantId::DragonFjord => entry::<DragonFjord, 8>(variant, date),
+                VariantId::JarringWords => entry::<JarringWords, 8>(variant, date),
+                VariantId::CreaMakerspace => entry::<CreaMakerspace, 8>(variant, date),
+                VariantId::Tetromino => entry::<Tetromino, 9>(variant, date),
+                VariantId::Weekday => entry::<Weekday, 10>(variant, date),
+            })
+            .collect();
+        Digest { date, entries }
+    }
+
+    /// Formats the digest as Markdown, with the solution grid behind a spoiler tag (`||...||`)
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        writeln!(md, "**Puzzle-a-day for {}**\n", self.title()).unwrap();
+        writeln!(md, "| Variant | Solutions | Difficulty |").unwrap();
+        writeln!(md, "|---|---:|---|").unwrap();
+        for entry in &self.entries {
+            writeln!(
+                md,
+                "| {} | {} | {} |",
+                entry.variant.name(),
+                entry.count,
+                entry.difficulty()
+            )
+            .unwrap();
+        }
+        if let Some((variant, grid)) = self.featured_grid() {
+            writeln!(md, "\nOne {} solution (spoiler):", variant.name()).unwrap();
+            for row in grid.lines() {
+                writeln!(md, "||{row}||").unwrap();
+            }
+        }
+        if self.is_unranked() {
+            writeln!(md, "\n_{RANK_NOTE}_").unwrap();
+        }
+        md
+    }
+
+    /// Formats the digest as plain text, with the solution grid after a spoiler warning
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        writeln!(text, "Puzzle-a-day for {}\n", self.title()).unwrap();
+        let width = self
+            .entries
+            .iter()
+            .map(|entry| entry.variant.name().len())
+            .max()
+            .unwrap_or(0);
+        for entry in &self.entries {
+            writeln!(
+                text,
+                "{:width$}  {:>5} solutions  difficulty {}",
+                entry.variant.name(),
+                entry.count,
+                entry.difficulty()
+            )
+            .unwrap();
+        }
+        if let Some((variant, grid)) = self.featured_grid() {
+            writeln!(text, "\nSPOILER - one {} solution:\n", variant.name()).unwrap();
+            text.push_str(grid);
+        }
+        if self.is_unranked() {
+            writeln!(text, "\n{RANK_NOTE}").unwrap();
+        }
+        text
+    }
+
+    /// The date, e.g. `Friday, December 25, 2020`
+    fn title(&self) -> String {
+        self.date.format("%A, %B %-d, %Y").to_string()
+    }
+
+    /// Whether any variant is missing its difficulty rank
+    fn is_unranked(&self) -> bool {
+        self.entries.iter().any(|entry| entry.rank.is_none())
+    }
+
+    /// Grid of the first variant with a solution
+    fn featured_grid(&self) -> Option<(VariantId, &str)> {
+        self.entries
+            .iter()
+            .find_map(|entry| Some((entry.variant, entry.grid.as_deref()?)))
+    }
+}
+
+impl DigestEntry {
+    /// The rank, e.g. `#12 of 365`, or `-` if there is no rank
+    fn difficulty(&self) -> String {
+        match self.rank {
+            Some((rank, dates)) => format!("#{rank} of {dates}"),
+            None => "-".to_owned(),
+        }
+    }
+}
+
+fn entry<V: Variant<N>, const N: usize>(variant: VariantId, date: NaiveDate) -> DigestEntry {
+    let (count, solution) = match SolutionDb::global().and_then(|db| db.get::<V, N>(date)) {
+        Some(entry) => (entry.count as usize, entry.solutions.into_iter().next()),
+        None => {
+            // Count by finding every solution once, in the order that makes the first one match `solve_once`
+            let solutions = V::board(date).solve(&V::pieces(), false);
+            (solutions.len(), solutions.into_iter().next())
+        }
+    };
+    DigestEntry {
+        variant,
+        count,
+        grid: solution.as_ref().map(emoji::render::<V, N>),
+        solution,
+        rank: year_rank::<V, N>(date, count),
+    }
+}
+
+/// Ranks `count` among the counts of every date in the date's year, if the installed database has them all
+fn year_rank<V: Variant<N>, const N: usize>(
+    date: NaiveDate,
+    count: usize,
+) -> Option<(usize, usize)> {
+    let db = SolutionDb::global()?;
+    let counts = NaiveDate::from_yo_opt(date.year(), 1)?
+        .iter_days()
+        .take_while(|d| d.year() == date.year())
+        .map(|d| db.get::<V, N>(d).map(|entry| entry.count as usize))
+        .collect::<Option<Vec<usize>>>()?;
+    let rank = counts.iter().filter(|&&c| c < count).count() + 1;
+    Some((rank, counts.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest() -> Digest {
+        // Only the quickest variants, without a database for ranks
+        let date = NaiveDate::from_ymd_opt(2020, 12, 25).unwrap();
+        let mut digest = Digest::for_variants(date, &VariantId::ALL[..2]);
+        assert_eq!(digest.entries[1].rank, None);
+        digest.entries[0].rank = Some((300, 366));
+        digest
+    }
+
+    #[test]
+    fn first_solution() {
+        let date = NaiveDate::from_ymd_opt(2020, 12, 25).unwrap();
+        let digest = Digest::for_variants(date, &VariantId::ALL[..1]);
+        assert_eq!(digest.entries[0].solution, DragonFjord::solve_once(date));
+    }
+
+    #[test]
+    fn markdown() {
+        let md = digest().to_markdown();
+        assert!(md.starts_with("**Puzzle-a-day for Friday, December 25, 2020**\n"));
+        assert!(md.contains("| dragon-fjord | 92 | #300 of 366 |\n"));
+        assert!(md.contains("| jarring-words | "));
+        assert!(md.contains(" | - |\n\nOne dragon-fjord solution (spoiler):\n||🟥🟥🟫🟫🟫🟫⬜||\n"));
+        assert!(md.ends_with(&format!("||\n\n_{RANK_NOTE}_\n")));
+    }
+
+    #[test]
+    fn text() {
+        let text = digest().to_text();
+        assert!(text.starts_with("Puzzle-a-day for Friday, December 25, 2020\n\n"));
+        assert!(text.contains("dragon-fjord      92 solutions  difficulty #300 of 366\n"));
+        assert!(text.contains("jarring-words   "));
+        assert!(text.contains("SPOILER - one dragon-fjord solution:\n\n🟥🟥🟫🟫🟫🟫⬜\n"));
+        assert!(text.ends_with(&format!("\n\n{RANK_NOTE}\n")));
+    }
+}
@@ -0,0 +1,125 @@
+//! Emoji grids of solutions, e.g. to share in a chat and verify later
+
+use crate::bitboard::BitBoard;
+use crate::board::Solution;
+use crate::render::base;
+use crate::variants::Variant;
+
+/// Emoji of each piece
+const EMOJI_PIECES: [&str; 10] = ["🟥", "🟧", "🟨", "🟩", "🟦", "🟪", "🟫", "⬛", "🔴", "🔵"];
+
+/// Emoji of board squares without a piece (i.e. the date)
+const EMOJI_OPEN: &str = "⭐";
+
+/// Emoji of squares that are not part of the board
+const EMOJI_OFF_BOARD: &str = "⬜";
+
+/// Renders a solution as rows of emoji, e.g. to share in a chat
+///
+/// Each piece has its own color, the date squares are stars, and rows and columns
+/// past the edge of the board are left out.
+pub fn render<V: Variant<N>, const N: usize>(solution: &Solution) -> String {
+    let base = base::<V, N>();
+    let on_board = |i: usize| base.0 & (1 << (63 - i)) == 0;
+    let width = (0..64)
+        .filter(|&i| on_board(i))
+        .map(|i| i % 8 + 1)
+        .max()
+        .unwrap_or(0);
+    let height = (0..64)
+        .filter(|&i| on_board(i))
+        .map(|i| i / 8 + 1)
+        .max()
+        .unwrap_or(0);
+
+    let mut grid = String::new();
+    for y in 0..height {
+        for x in 0..width {
+            let i = y * 8 + x;
+            let piece = solution.0.iter().position(|bb| bb.0 & (1 << (63 - i)) != 0);
+            grid.push_str(match piece {
+                Some(p) => EMOJI_PIECES[p % EMOJI_PIECES.len()],
+                None if on_board(i) => EMOJI_OPEN,
+                None => EMOJI_OFF_BOARD,
+            });
+        }
+        grid.push('\n');
+    }
+    grid
+}
+
+/// Parses a solution rendered by [`render`], e.g. to verify a shared solution
+///
+/// Variation selectors (which some chat apps add after emoji) and whitespace are ignored.
+pub fn parse(s: &str) -> Result<Solution, &'static str> {
+    let rows: Vec<&str> = s.lines().filter(|line| !line.trim().is_empty()).collect();
+    if rows.len() > 8 {
+        return Err("Expected at most 8 rows");
+    }
+
+    let mut pieces = Vec::new();
+    for (y, row) in rows.iter().enumerate() {
+        let squares: Vec<char> = row
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '\u{fe0f}')
+            .collect();
+        if squares.len() > 8 {
+            return Err("Expected at most 8 squares in each row");
+        }
+        for (x, square) in squares.iter().enumerate() {
+            let mut buf = [0; 4];
+            let index = match &*square.encode_utf8(&mut buf) {
+                EMOJI_OPEN | EMOJI_OFF_BOARD => continue,
+                square => EMOJI_PIECES
+                    .iter()
+                    .position(|&e| e == square)
+                    .ok_or("Invalid square")?,
+            };
+            if pieces.len() <= index {
+                pieces.resize(index + 1, BitBoard::new(0));
+            }
+            pieces[index] |= BitBoard::new(1 << (63 - (y * 8 + x)));
+        }
+    }
+    Ok(Solution(pieces))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants::{DragonFjord, Weekday};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn render_grid() {
+        let date = NaiveDate::from_ymd_opt(2020, 12, 25).unwrap();
+        let grid = render::<DragonFjord, 8>(&DragonFjord::solve_once(date).unwrap());
+        let rows: Vec<&str> = grid.lines().collect();
+        assert_eq!(rows.len(), 7);
+        assert_eq!(rows[0], "🟥🟥🟫🟫🟫🟫⬜");
+        assert!(rows.iter().all(|row| row.chars().count() == 7));
+        assert_eq!(grid.matches(EMOJI_OPEN).count(), 2);
+
+        let grid = render::<Weekday, 10>(&Weekday::solve_once(date).unwrap());
+        assert_eq!(grid.lines().count(), 8);
+        assert_eq!(grid.matches(EMOJI_OPEN).count(), 3);
+    }
+
+    #[test]
+    fn parse_grid() {
+        let date = NaiveDate::from_ymd_opt(2020, 12, 25).unwrap();
+        let solution = Weekday::solve_once(date).unwrap();
+        assert_eq!(parse(&render::<Weekday, 10>(&solution)), Ok(solution));
+
+        // As pasted from a chat app, with variation selectors
+        let solution = DragonFjord::solve_once(date).unwrap();
+        let shared = render::<DragonFjord, 8>(&solution).replace('⬛', "⬛\u{fe0f}");
+        assert_eq!(parse(&shared), Ok(solution));
+
+        assert_eq!(parse("🟥🟥x"), Err("Invalid square"));
+        assert_eq!(
+            parse("🟥🟥🟥🟥🟥🟥🟥🟥🟥"),
+            Err("Expected at most 8 squares in each row")
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! C API for native callers, e.g. C++ tools or Python via `ctypes`
+//!
+//! The header, [include/today_puzzle.h](../include/today_puzzle.h), is generated by `just header`.
+//! Solutions are arrays of piece bitmaps, where bit 63 is the top-left square of the board.
+
+use crate::solver::{AnySolver, PieceOrder, SolveOptions};
+use crate::variants::VariantId;
+use chrono::NaiveDate;
+use std::ffi::{c_char, CStr};
+use std::ptr;
+
+pub const TP_VARIANT_DRAGON_FJORD: u32 = VariantId::DragonFjord as u32;
+pub const TP_VARIANT_JARRING_WORDS: u32 = VariantId::JarringWords as u32;
+pub const TP_VARIANT_CREA_MAKERSPACE: u32 = VariantId::CreaMakerspace as u32;
+pub const TP_VARIANT_TETROMINO: u32 = VariantId::Tetromino as u32;
+pub const TP_VARIANT_WEEKDAY: u32 = VariantId::Weekday as u32;
+
+/// Variant names, NUL-terminated for C, in the order of [`VariantId::ALL`]
+const VARIANT_NAMES: [&CStr; 5] = [
+    c"dragon-fjord",
+    c"jarring-words",
+    c"crea-makerspace",
+    c"tetromino",
+    c"weekday",
+];
+
+/// Finds the solutions for a variant and date, one at a time
+pub struct TpSolver {
+    solver: AnySolver<'static>,
+}
+
+/// Number of variants, which are numbered from 0 (see the `TP_VARIANT_*` constants)
+#[no_mangle]
+pub extern "C" fn tp_variant_count() -> u32 {
+    VariantId::ALL.len() as u32
+}
+
+/// Name of a variant (e.g. `dragon-fjord`), or NULL if the variant doesn't exist
+///
+/// The string is static, so must not be freed.
+#[no_mangle]
+pub extern "C" fn tp_variant_name(variant: u32) -> *const c_char {
+    match VariantId::try_from(variant) {
+        Ok(id) => VARIANT_NAMES[id as usize].as_ptr(),
+        Err(_) => ptr::null(),
+    }
+}
+
+/// Creates a solver for a variant and date, or returns NULL if either doesn't exist
+///
+/// Free the solver with [`tp_solver_free`].
+#[no_mangle]
+pub extern "C" fn tp_solver_new(variant: u32, year: i32, month: u32, day: u32) -> *mut TpSolver {
+    let Ok(id) = VariantId::try_from(variant) else {
+        return ptr::null_mut();
+    };
+    let Some(date) = NaiveDate::from_ymd_opt(year, month, day) else {
+        return ptr::null_mut();
+    };
+    match id.solver(date.into()) {
+        Ok(solver) => Box::into_raw(Box::new(TpSolver { solver })),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Number of pieces in each of the solver's solutions
+///
+/// # Safety
+///
+/// `solver` must have been created by [`tp_solver_new`] and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn tp_solver_piece_count(solver: *const TpSolver) -> usize {
+    (*solver).solver.piece_count()
+}
+
+/// Finds the next solution, writing up to `len` piece bitmaps to `pieces`
+///
+/// Returns the number of pieces in the solution, or 0 once every solution has been found.
+///
+/// # Safety
+///
+/// `solver` must have been created by [`tp_solver_new`] and not yet freed,
+/// and `pieces` must be valid for writing `len` values.
+#[no_mangle]
+pub unsafe extern "C" fn tp_solver_next(
+    solver: *mut TpSolver,
+    pieces: *mut u64,
+    len: usize,
+) -> usize {
+    let result = (*solver).solver.run(&SolveOptions::first());
+    let Some(solution) = result.solutions.first() else {
+        return 0;
+    };
+    for (i, bitboard) in solution.0.iter().take(len).enumerate() {
+        *pieces.add(i) = bitboard.0;
+    }
+    solution.0.len()
+}
+
+/// Number of solutions found so far
+///
+/// # Safety
+///
+/// `solver` must have been created by [`tp_solver_new`] and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn tp_solver_count(solver: *const TpSolver) -> u64 {
+    (*solver).solver.solution_count()
+}
+
+/// Frees a solver created by [`tp_solver_new`] (NULL is ignored)
+///
+/// # Safety
+///
+/// `solver` must be NULL, or created by [`tp_solver_new`] and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn tp_solver_free(solver: *mut TpSolver) {
+    if !solver.is_null() {
+        drop(Box::from_raw(solver));
+    }
+}
+
+/// Counts the solutions for a variant and date, or returns -1 if either doesn't exist
+#[no_mangle]
+pub extern "C" fn tp_count_solutions(variant: u32, year: i32, month: u32, day: u32) -> i64 {
+    let Ok(id) = VariantId::try_from(variant) else {
+        return -1;
+    };
+    let Some(date) = NaiveDate::from_ymd_opt(year, month, day) else {
+        return -1;
+    };
+    // The order solutions are found in doesn't matter for counting, so place the most constrained pieces first
+    match id.solver_with_order(date.into(), PieceOrder::FewestPlacements) {
+        Ok(mut solver) => {
+            solver.run(&SolveOptions::default());
+            solver.solution_count() as i64
+        }
+        Err(_) => -1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variant_names() {
+        for id in VariantId::ALL {
+            let name = unsafe { CStr::from_ptr(tp_variant_name(id as u32)) };
+            assert_eq!(name.to_str(), Ok(id.name()));
+        }
+        assert!(tp_variant_name(tp_variant_count()).is_null());
+    }
+
+    #[test]
+    fn iterate_solutions() {
+        assert!(tp_solver_new(TP_VARIANT_DRAGON_FJORD, 2021, 2, 29).is_null());
+        assert!(tp_solver_new(5, 2020, 12, 25).is_null());
+        assert_eq!(
+            tp_count_solutions(TP_VARIANT_DRAGON_FJORD, 2020, 12, 25),
+            92
+        );
+
+        let solver = tp_solver_new(TP_VARIANT_WEEKDAY, 2020, 12, 25);
+        assert!(!solver.is_null());
+        unsafe {
+            assert_eq!(tp_solver_piece_count(solver), 10);
+            let mut pieces = [0u64; 10];
+            assert_eq!(
+                tp_solver_next(solver, pieces.as_mut_ptr(), pieces.len()),
+                10
+            );
+            assert!(pieces.iter().all(|&p| p != 0));
+            // A short buffer only gets the first pieces
+            let mut first = [0u64; 2];
+            assert_eq!(tp_solver_next(solver, first.as_mut_ptr(), first.len()), 10);
+            assert_eq!(tp_solver_count(solver), 2);
+            tp_solver_free(solver);
+        }
+    }
+}
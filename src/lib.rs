@@ -8,8 +8,22 @@ static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 pub mod bitboard;
 pub mod bitpiece;
 pub mod board;
+pub mod calendar;
+pub mod challenge;
+pub mod date;
+pub mod db;
+pub mod designer;
+pub mod digest;
+pub mod emoji;
 pub mod piece;
+pub mod polyomino;
+pub mod render;
+pub mod solver;
 pub mod variants;
 
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "python")]
+mod python;
 #[cfg(feature = "wasm")]
 mod wasm;
@@ -1,18 +1,19 @@
+use crate::bitboard::BitBoard;
 use crate::bitpiece::BitPiece;
 
 // Unique variations of a given piece - rotations and reflections calculated at compile time
 pub const PIECE_RECT: Variations<2> = Variations::<2>::rotations(0x0077); // 6 squares
-pub const PIECE_U: Variations<4> = Variations::<4>::rotations(0x0313); // 5 squares
-pub const PIECE_CORNER: Variations<4> = Variations::<4>::rotations(0x0117); // 5 squares
-pub const PIECE_TALL_S: Variations<4> = Variations::<4>::rotations_and_reflections(0x0326); // 5 squares
-pub const PIECE_TALL_L: Variations<8> = Variations::<8>::rotations_and_reflections(0x001F); // 5 squares
-pub const PIECE_LONG_Z: Variations<8> = Variations::<8>::rotations_and_reflections(0x003E); // 5 squares
-pub const PIECE_UNEVEN_T: Variations<8> = Variations::<8>::rotations_and_reflections(0x002F); // 5 squares
-pub const PIECE_SIX: Variations<8> = Variations::<8>::rotations_and_reflections(0x0331); // 5 squares
+pub const PIECE_U: Variations<4> = Variations::<4>::rotations(0x0313); // 5 squares (U pentomino)
+pub const PIECE_CORNER: Variations<4> = Variations::<4>::rotations(0x0117); // 5 squares (V pentomino)
+pub const PIECE_TALL_S: Variations<4> = Variations::<4>::rotations_and_reflections(0x0326); // 5 squares (Z pentomino)
+pub const PIECE_TALL_L: Variations<8> = Variations::<8>::rotations_and_reflections(0x001F); // 5 squares (L pentomino)
+pub const PIECE_LONG_Z: Variations<8> = Variations::<8>::rotations_and_reflections(0x003E); // 5 squares (N pentomino)
+pub const PIECE_UNEVEN_T: Variations<8> = Variations::<8>::rotations_and_reflections(0x002F); // 5 squares (Y pentomino)
+pub const PIECE_SIX: Variations<8> = Variations::<8>::rotations_and_reflections(0x0331); // 5 squares (P pentomino)
 
-pub const PIECE_W: Variations<4> = Variations::<4>::rotations(0x0631); // 5 squares
+pub const PIECE_W: Variations<4> = Variations::<4>::rotations(0x0631); // 5 squares (W pentomino)
 pub const PIECE_H: Variations<8> = Variations::<8>::rotations_and_reflections(0x0175); // 6 squares
-pub const PIECE_TALL_T: Variations<4> = Variations::<4>::rotations(0x0227); // 5 squares
+pub const PIECE_TALL_T: Variations<4> = Variations::<4>::rotations(0x0227); // 5 squares (T pentomino)
 pub const PIECE_SQUARE: Variations<1> = Variations::<1>::new(0x0033); // 4 squares
 pub const PIECE_L: Variations<8> = Variations::<8>::rotations_and_reflections(0x0017); // 4 squares
 pub const PIECE_T: Variations<4> = Variations::<4>::rotations(0x0027); // 4 squares
@@ -23,12 +24,34 @@ pub const PIECE_Z: Variations<4> = Variations::<4>::rotations_and_reflections(0x
 pub struct Variations<const N: usize>(pub [BitPiece; N]);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Piece {
-    pub variations: &'static [BitPiece],
+pub struct Piece<'a> {
+    pub variations: &'a [BitPiece],
+}
+
+impl Piece<'_> {
+    /// Finds the variation index and (x, y) position that covers exactly the squares of `bb`
+    pub fn find_placement(&self, bb: BitBoard) -> Option<(usize, usize, usize)> {
+        // An empty board would shift variations off the board
+        if bb == BitBoard::new(0) {
+            return None;
+        }
+        self.variations
+            .iter()
+            .enumerate()
+            .find_map(|(v, variation)| {
+                let offset =
+                    bb.0.trailing_zeros()
+                        .checked_sub(variation.to_bitboard(0, 0).0.trailing_zeros())?
+                        as usize;
+                let (x, y) = (offset % 8, offset / 8);
+                (x + variation.width() <= 8 && variation.to_bitboard(x, y) == bb)
+                    .then_some((v, x, y))
+            })
+    }
 }
 
 impl<const N: usize> Variations<N> {
-    pub const fn as_ref(&'static self) -> Piece {
+    pub const fn as_ref(&'static self) -> Piece<'static> {
         Piece {
             variations: &self.0,
         }
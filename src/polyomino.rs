@@ -0,0 +1,350 @@
+use crate::bitpiece::BitPiece;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Largest polyomino that always fits on the 8x8 grid
+pub const MAX_CELLS: usize = 8;
+
+/// Standard names of the 12 free pentominoes, drawn one row per string
+const PENTOMINOES: [(char, [&str; 3]); 12] = [
+    ('F', [".XX", "XX.", ".X."]),
+    ('I', ["XXXXX", "", ""]),
+    ('L', ["XXXX", "X...", ""]),
+    ('N', ["XXX.", "..XX", ""]),
+    ('P', ["XX", "XX", "X."]),
+    ('T', ["XXX", ".X.", ".X."]),
+    ('U', ["X.X", "XXX", ""]),
+    ('V', ["X..", "X..", "XXX"]),
+    ('W', ["X..", "XX.", ".XX"]),
+    ('X', [".X.", "XXX", ".X."]),
+    ('Y', ["XXXX", ".X..", ""]),
+    ('Z', ["XX.", ".X.", ".XX"]),
+];
+
+/// A shape made of orthogonally connected squares, aligned to the LSB of an 8x8 grid
+///
+/// Squares are numbered `y * 8 + x` like [`BitBoard`](crate::bitboard::BitBoard),
+/// so unlike [`BitPiece`] a polyomino can be up to 8 squares wide or tall.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Polyomino(pub u64);
+
+/// The rotations and reflections that map a polyomino onto itself
+///
+/// Each class determines how many unique orientations the polyomino has.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+    /// Every rotation and reflection (1 orientation)
+    Full,
+    /// Quarter turns, but no reflections (2 orientations)
+    QuarterTurn,
+    /// Half turns, and reflections across the horizontal and vertical axes (2 orientations)
+    AxisMirrors,
+    /// Half turns, and reflections across both diagonals (2 orientations)
+    DiagonalMirrors,
+    /// Half turns only (4 orientations)
+    HalfTurn,
+    /// Reflection across the horizontal or vertical axis only (4 orientations)
+    AxisMirror,
+    /// Reflection across a diagonal only (4 orientations)
+    DiagonalMirror,
+    /// No symmetry (8 orientations)
+    None,
+}
+
+impl Symmetry {
+    /// Number of unique orientations (rotations and reflections) of a polyomino with this symmetry
+    pub const fn orientations(self) -> usize {
+        match self {
+            Symmetry::Full => 1,
+            Symmetry::QuarterTurn | Symmetry::AxisMirrors | Symmetry::DiagonalMirrors => 2,
+            Symmetry::HalfTurn | Symmetry::AxisMirror | Symmetry::DiagonalMirror => 4,
+            Symmetry::None => 8,
+        }
+    }
+}
+
+impl fmt::Debug for Polyomino {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Polyomino(0x{:04x})", self.0)
+    }
+}
+
+impl fmt::Display for Polyomino {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rows: Vec<String> = (0..self.height())
+            .map(|y| {
+                (0..self.width())
+                    .map(|x| if self.contains(x, y) { "X " } else { ". " })
+                    .collect()
+            })
+            .collect();
+        write!(f, "{}", rows.join("\n"))
+    }
+}
+
+impl From<BitPiece> for Polyomino {
+    fn from(piece: BitPiece) -> Polyomino {
+        Polyomino(piece.to_bitboard(0, 0).0).align()
+    }
+}
+
+impl Polyomino {
+    /// Builds a polyomino from squares, shifting it so the smallest x and y are 0
+    ///
+    /// Returns `None` if the squares don't fit within 8x8.
+    pub fn from_cells(cells: &[(i32, i32)]) -> Option<Polyomino> {
+        let min_x = cells.iter().map(|c| c.0).min()?;
+        let min_y = cells.iter().map(|c| c.1).min()?;
+        let mut bits = 0;
+        for &(x, y) in cells {
+            let (x, y) = (x - min_x, y - min_y);
+            if x >= 8 || y >= 8 {
+                return None;
+            }
+            bits |= 1 << (y * 8 + x);
+        }
+        Some(Polyomino(bits))
+    }
+
+    /// Squares of the polyomino as (x, y) coordinates
+    pub fn cell_coords(self) -> Vec<(i32, i32)> {
+        (0..64)
+            .filter(|i| self.0 & (1 << i) != 0)
+            .map(|i| (i % 8, i / 8))
+            .collect()
+    }
+
+    /// Number of squares in the polyomino
+    pub const fn cells(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub fn width(self) -> i32 {
+        self.cell_coords()
+            .iter()
+            .map(|c| c.0 + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn height(self) -> i32 {
+        self.cell_coords()
+            .iter()
+            .map(|c| c.1 + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn contains(self, x: i32, y: i32) -> bool {
+        self.0 & (1 << (y * 8 + x)) != 0
+    }
+
+    /// Shifts the shape so the smallest x and y are 0
+    fn align(self) -> Polyomino {
+        Polyomino::from_cells(&self.cell_coords()).unwrap_or(self)
+    }
+
+    fn transform(self, f: impl Fn((i32, i32)) -> (i32, i32)) -> Polyomino {
+        let cells: Vec<_> = self.cell_coords().into_iter().map(f).collect();
+        Polyomino::from_cells(&cells).expect("transformed polyomino fits on the grid")
+    }
+
+    /// Rotate 90 deg
+    pub fn rotate(self) -> Polyomino {
+        self.transform(|(x, y)| (-y, x))
+    }
+
+    /// Horizontal flip
+    pub fn flip(self) -> Polyomino {
+        self.transform(|(x, y)| (-x, y))
+    }
+
+    /// Reflection across the main diagonal
+    fn transpose(self) -> Polyomino {
+        self.transform(|(x, y)| (y, x))
+    }
+
+    /// Unique rotations and reflections of the polyomino
+    pub fn orientations(self) -> Vec<Polyomino> {
+        let mut orientations = BTreeSet::new();
+        let mut p = self.align();
+        for _ in 0..4 {
+            orientations.insert(p);
+            orientations.insert(p.flip());
+            p = p.rotate();
+        }
+        orientations.into_iter().collect()
+    }
+
+    /// The orientation used to identify a free polyomino (the smallest of its orientations)
+    pub fn canonical(self) -> Polyomino {
+        self.orientations()[0]
+    }
+
+    pub fn symmetry(self) -> Symmetry {
+        let p = self.align();
+        let quarter = p.rotate() == p;
+        let half = p.rotate().rotate() == p;
+        let axis = p.flip() == p || p.rotate().rotate().flip() == p;
+        let diagonal = p.transpose() == p || p.rotate().rotate().transpose() == p;
+        match (quarter, half, axis, diagonal) {
+            (true, _, true, _) => Symmetry::Full,
+            (true, _, false, _) => Symmetry::QuarterTurn,
+            (false, true, true, _) => Symmetry::AxisMirrors,
+            (false, true, false, true) => Symmetry::DiagonalMirrors,
+            (false, true, false, false) => Symmetry::HalfTurn,
+            (false, false, true, _) => Symmetry::AxisMirror,
+            (false, false, false, true) => Symmetry::DiagonalMirror,
+            (false, false, false, false) => Symmetry::None,
+        }
+    }
+
+    /// Converts to a [`BitPiece`] if the polyomino fits within 4x4
+    pub fn to_bitpiece(self) -> Option<BitPiece> {
+        let p = self.align();
+        if p.width() > 4 || p.height() > 4 {
+            return None;
+        }
+        let bits = p
+            .cell_coords()
+            .iter()
+            .fold(0, |bits, &(x, y)| bits | 1 << (y * 4 + x));
+        Some(BitPiece::new(bits))
+    }
+
+    /// Standard name of a pentomino (F, I, L, N, P, T, U, V, W, X, Y, or Z)
+    pub fn name(self) -> Option<char> {
+        if self.cells() != 5 {
+            return None;
+        }
+        let canonical = self.canonical();
+        PENTOMINOES
+            .iter()
+            .map(|&(name, _)| name)
+            .find(|&name| pentomino(name).map(Polyomino::canonical) == Some(canonical))
+    }
+}
+
+/// Looks up a pentomino by its standard name
+pub fn pentomino(name: char) -> Option<Polyomino> {
+    let (_, rows) = PENTOMINOES.iter().find(|(n, _)| *n == name)?;
+    let cells: Vec<_> = rows
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.chars()
+                .enumerate()
+                .filter(|(_, c)| *c == 'X')
+                .map(move |(x, _)| (x as i32, y as i32))
+        })
+        .collect();
+    Polyomino::from_cells(&cells)
+}
+
+/// Every fixed polyomino with `n` squares (rotations and reflections are distinct)
+///
+/// Panics if `n` is larger than [`MAX_CELLS`].
+pub fn fixed(n: usize) -> Vec<Polyomino> {
+    assert!(
+        n <= MAX_CELLS,
+        "polyominoes larger than {MAX_CELLS} squares are not supported"
+    );
+    let mut shapes = BTreeSet::new();
+    if n > 0 {
+        shapes.insert(Polyomino(1));
+    }
+    for _ in 1..n {
+        shapes = shapes.into_iter().flat_map(grow).collect();
+    }
+    shapes.into_iter().collect()
+}
+
+/// Every free polyomino with `n` squares (rotations and reflections are the same shape)
+///
+/// Each polyomino is returned in its [`canonical`](Polyomino::canonical) orientation.
+pub fn free(n: usize) -> Vec<Polyomino> {
+    let shapes: BTreeSet<_> = fixed(n).into_iter().map(Polyomino::canonical).collect();
+    shapes.into_iter().collect()
+}
+
+/// Every polyomino formed by adding one square next to an existing square
+fn grow(p: Polyomino) -> Vec<Polyomino> {
+    let cells = p.cell_coords();
+    let mut grown = Vec::new();
+    for &(x, y) in &cells {
+        for neighbor in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+            if cells.contains(&neighbor) {
+                continue;
+            }
+            let mut new_cells = cells.clone();
+            new_cells.push(neighbor);
+            if let Some(new) = Polyomino::from_cells(&new_cells) {
+                grown.push(new);
+            }
+        }
+    }
+    grown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::*;
+
+    #[test]
+    fn counts() {
+        // https://oeis.org/A001168 and https://oeis.org/A000105
+        let fixed_counts: Vec<_> = (1..=7).map(|n| fixed(n).len()).collect();
+        assert_eq!(fixed_counts, [1, 2, 6, 19, 63, 216, 760]);
+        let free_counts: Vec<_> = (1..=7).map(|n| free(n).len()).collect();
+        assert_eq!(free_counts, [1, 1, 2, 5, 12, 35, 108]);
+    }
+
+    #[test]
+    fn symmetry_matches_orientations() {
+        for n in 1..=7 {
+            for p in free(n) {
+                assert_eq!(p.symmetry().orientations(), p.orientations().len(), "\n{p}");
+            }
+        }
+        assert_eq!(pentomino('X').unwrap().symmetry(), Symmetry::Full);
+        assert_eq!(pentomino('I').unwrap().symmetry(), Symmetry::AxisMirrors);
+        assert_eq!(pentomino('Z').unwrap().symmetry(), Symmetry::HalfTurn);
+        assert_eq!(pentomino('T').unwrap().symmetry(), Symmetry::AxisMirror);
+        assert_eq!(pentomino('W').unwrap().symmetry(), Symmetry::DiagonalMirror);
+        assert_eq!(pentomino('F').unwrap().symmetry(), Symmetry::None);
+    }
+
+    #[test]
+    fn pentomino_names() {
+        let names: BTreeSet<_> = free(5).into_iter().filter_map(Polyomino::name).collect();
+        assert_eq!(names.len(), 12);
+        assert!(pentomino('I').unwrap().to_bitpiece().is_none());
+        assert_eq!(pentomino('Q'), None);
+    }
+
+    #[test]
+    fn piece_names() {
+        let name = |piece: Piece| Polyomino::from(piece.variations[0]).name();
+        assert_eq!(name(PIECE_U.as_ref()), Some('U'));
+        assert_eq!(name(PIECE_CORNER.as_ref()), Some('V'));
+        assert_eq!(name(PIECE_TALL_S.as_ref()), Some('Z'));
+        assert_eq!(name(PIECE_TALL_L.as_ref()), Some('L'));
+        assert_eq!(name(PIECE_LONG_Z.as_ref()), Some('N'));
+        assert_eq!(name(PIECE_UNEVEN_T.as_ref()), Some('Y'));
+        assert_eq!(name(PIECE_SIX.as_ref()), Some('P'));
+        assert_eq!(name(PIECE_W.as_ref()), Some('W'));
+        assert_eq!(name(PIECE_TALL_T.as_ref()), Some('T'));
+        assert_eq!(name(PIECE_RECT.as_ref()), None);
+    }
+
+    #[test]
+    fn bitpiece_round_trip() {
+        for p in free(5) {
+            if let Some(piece) = p.to_bitpiece() {
+                assert_eq!(Polyomino::from(piece), p);
+                assert_eq!(piece.orientations().len(), p.orientations().len());
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Python bindings, built as the `today_puzzle` module with [maturin](https://www.maturin.rs/) (see `pyproject.toml`)
+//!
+//! Solutions are exposed as piece placements, or as `u64` bitmaps that convert directly to numpy arrays
+//! (e.g. `numpy.array(solution.bitmaps, dtype=numpy.uint64)`).
+
+// The `#[pyfunction]` wrappers convert `PyResult` errors into themselves
+#![allow(clippy::useless_conversion)]
+
+use crate::board::Solution;
+use crate::solver::{PieceOrder, SolveOptions};
+use crate::variants::{
+    BoardDate, CreaMakerspace, DragonFjord, JarringWords, Tetromino, Variant, VariantId, Weekday,
+};
+use chrono::NaiveDate;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDate, PyDateAccess};
+
+#[pymethods]
+impl VariantId {
+    /// Name of the variant, as used by the command line (e.g. `dragon-fjord`)
+    #[getter]
+    #[pyo3(name = "name")]
+    fn py_name(&self) -> &'static str {
+        self.name()
+    }
+
+    #[getter]
+    fn piece_count(&self) -> usize {
+        self.pieces().len()
+    }
+
+    /// Looks up a variant by its name
+    #[staticmethod]
+    fn from_name(name: &str) -> PyResult<VariantId> {
+        name.parse().map_err(PyValueError::new_err)
+    }
+}
+
+/// A solution, with a bitmap of the squares covered by each piece
+#[pyclass(frozen, name = "Solution", module = "today_puzzle")]
+pub struct PySolution {
+    variant: VariantId,
+    solution: Solution,
+}
+
+#[pymethods]
+impl PySolution {
+    #[getter]
+    fn variant(&self) -> VariantId {
+        self.variant
+    }
+
+    /// Squares covered by each piece, where bit 63 is the top-left square
+    #[getter]
+    fn bitmaps(&self) -> Vec<u64> {
+        self.solution.0.iter().map(|bitboard| bitboard.0).collect()
+    }
+
+    /// `(piece, variation, x, y)` of each piece, as in challenge codes
+    #[getter]
+    fn placements(&self) -> Vec<(usize, usize, usize, usize)> {
+        self.variant
+            .pieces()
+            .iter()
+            .zip(&self.solution.0)
+            .enumerate()
+            .map(|(piece, (p, bitboard))| {
+                let (variation, x, y) = p
+                    .find_placement(*bitboard)
+                    .expect("solution piece matches a variation");
+                (piece, variation, x, y)
+            })
+            .collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.solution.0.len()
+    }
+
+    fn __str__(&self) -> String {
+        self.solution.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Solution {} {:?}>", self.variant.name(), self.bitmaps())
+    }
+}
+
+/// Every variant
+#[pyfunction]
+fn variants() -> Vec<VariantId> {
+    VariantId::ALL.to_vec()
+}
+
+/// Finds the first solution for a `datetime.date`, or returns `None` if there are none
+#[pyfunction]
+fn solve_once(
+    py: Python<'_>,
+    variant: VariantId,
+    date: &Bound<'_, PyDate>,
+) -> PyResult<Option<PySolution>> {
+    let date = board_date(date)?;
+    let solutions = py.allow_threads(|| solve(variant, date, true))?;
+    Ok(solutions.into_iter().next())
+}
+
+/// Finds every solution for a `datetime.date`
+#[pyfunction]
+fn solve_fully(
+    py: Python<'_>,
+    variant: VariantId,
+    date: &Bound<'_, PyDate>,
+) -> PyResult<Vec<PySolution>> {
+    let date = board_date(date)?;
+    py.allow_threads(|| solve(variant, date, false))
+}
+
+/// Counts the solutions for a `datetime.date`, without keeping them
+#[pyfunction]
+fn count(py: Python<'_>, variant: VariantId, date: &Bound<'_, PyDate>) -> PyResult<u64> {
+    let mut solver = variant
+        .solver_with_order(board_date(date)?, PieceOrder::FewestPlacements)
+        .map_err(PyValueError::new_err)?;
+    py.allow_threads(|| {
+        solver.run(&SolveOptions::default());
+        Ok(solver.solution_count())
+    })
+}
+
+fn board_date(date: &Bound<'_, PyDate>) -> PyResult<BoardDate> {
+    let (year, month, day) = (date.get_year(), date.get_month(), date.get_day());
+    let date = NaiveDate::from_ymd_opt(year, month.into(), day.into())
+        .ok_or_else(|| PyValueError::new_err("Invalid date"))?;
+    Ok(date.into())
+}
+
+fn solve(variant: VariantId, date: BoardDate, only_first: bool) -> PyResult<Vec<PySolution>> {
+    fn solve_as<V: Variant<N>, const N: usize>(
+        date: BoardDate,
+        only_first: bool,
+    ) -> Result<Vec<Solution>, &'static str> {
+        if only_first {
+            Ok(V::solve_once_at(date)?.into_iter().collect())
+        } else {
+            V::solve_fully_at(date)
+        }
+    }
+
+    let solutions = match variant {
+        VariantId::DragonFjord => solve_as::<DragonFjord, 8>(date, only_first),
+        VariantId::JarringWords => solve_as::<JarringWords, 8>(date, only_first),
+        VariantId::CreaMakerspace => solve_as::<CreaMakerspace, 8>(date, only_first),
+        VariantId::Tetromino => solve_as::<Tetromino, 9>(date, only_first),
+        VariantId::Weekday => solve_as::<Weekday, 10>(date, only_first),
+    };
+    Ok(solutions
+        .map_err(PyValueError::new_err)?
+        .into_iter()
+        .map(|solution| PySolution { variant, solution })
+        .collect())
+}
+
+#[pymodule]
+fn today_puzzle(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<VariantId>()?;
+    m.add_class::<PySolution>()?;
+    m.add_function(wrap_pyfunction!(variants, m)?)?;
+    m.add_function(wrap_pyfunction!(solve_once, m)?)?;
+    m.add_function(wrap_pyfunction!(solve_fully, m)?)?;
+    m.add_function(wrap_pyfunction!(count, m)?)?;
+    Ok(())
+}
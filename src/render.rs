@@ -0,0 +1,136 @@
+use crate::bitboard::BitBoard;
+use crate::board::Solution;
+use crate::variants::{BoardDate, Variant};
+use std::fmt::Write;
+
+/// Size of each square in SVG output
+const SQUARE: usize = 40;
+
+/// Fill color of each piece in SVG output
+const COLORS: [&str; 10] = [
+    "#e6194b", "#3cb44b", "#ffe119", "#4363d8", "#f58231", "#911eb4", "#46f0f0", "#f032e6",
+    "#bcf60c", "#fabebe",
+];
+
+/// Squares that are not part of the variant's board
+pub fn base<V: Variant<N>, const N: usize>() -> BitBoard {
+    // The date squares of any two dates that share no squares leave only the base in common
+    let first = V::board_at(BoardDate::new(1, 1).with_weekday(chrono::Weekday::Sun));
+    let second = V::board_at(BoardDate::new(2, 2).with_weekday(chrono::Weekday::Mon));
+    first.expect("valid date").combined & second.expect("valid date").combined
+}
+
+/// Label (e.g. `Jan`, `31`, or `Sun`) of each square on the variant's board, indexed by bit
+pub fn labels<V: Variant<N>, const N: usize>() -> Vec<Option<String>> {
+    let base = base::<V, N>();
+    // Squares shared by two dates that differ in everything except the labeled square
+    let shared = |a: BoardDate, b: BoardDate| {
+        let a = V::board_at(a).expect("valid date").combined;
+        let b = V::board_at(b).expect("valid date").combined;
+        a & b & !base
+    };
+
+    let mut labels = vec![None; 64];
+    let mut set = |bb: BitBoard, label: String| {
+        if bb.0 != 0 {
+            labels[bb.0.trailing_zeros() as usize] = Some(label);
+        }
+    };
+    for (month, name) in (1..).zip([
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ]) {
+        let a = BoardDate::new(month, 1).with_weekday(chrono::Weekday::Sun);
+        let b = BoardDate::new(month, 2).with_weekday(chrono::Weekday::Mon);
+        set(shared(a, b), name.to_owned());
+    }
+    for day in 1..=31 {
+        let a = BoardDate::new(1, day).with_weekday(chrono::Weekday::Sun);
+        let b = BoardDate::new(2, day).with_weekday(chrono::Weekday::Mon);
+        set(shared(a, b), day.to_string());
+    }
+    for weekday in std::iter::successors(Some(chrono::Weekday::Sun), |d| Some(d.succ())).take(7) {
+        let a = BoardDate::new(1, 1).with_weekday(weekday);
+        let b = BoardDate::new(2, 2).with_weekday(weekday);
+        set(shared(a, b), weekday.to_string());
+    }
+    labels
+}
+
+/// Renders a solution as an SVG image, with each piece in its own color and the uncovered squares labeled
+pub fn svg<V: Variant<N>, const N: usize>(solution: &Solution) -> String {
+    let base = base::<V, N>();
+    let labels = labels::<V, N>();
+    let size = SQUARE * 8;
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {size} {size}" width="{size}" height="{size}">"#
+    )
+    .unwrap();
+    for i in 0..64 {
+        let bit = 63 - i;
+        if base.0 & (1 << bit) != 0 {
+            continue;
+        }
+        let (x, y) = ((i % 8) * SQUARE, (i / 8) * SQUARE);
+        let piece = solution.0.iter().position(|bb| bb.0 & (1 << bit) != 0);
+        let fill = piece.map_or("#ffffff", |p| COLORS[p % COLORS.len()]);
+        writeln!(
+            svg,
+            r##"<rect x="{x}" y="{y}" width="{SQUARE}" height="{SQUARE}" fill="{fill}" stroke="#333333"/>"##
+        )
+        .unwrap();
+        if let (None, Some(label)) = (piece, &labels[bit]) {
+            writeln!(
+                svg,
+                r#"<text x="{}" y="{}" font-family="sans-serif" font-size="14" text-anchor="middle" dominant-baseline="central">{label}</text>"#,
+                x + SQUARE / 2,
+                y + SQUARE / 2
+            )
+            .unwrap();
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants::{
+        DragonFjord, Tetromino, Weekday, BITBOARD_STANDARD, BITBOARD_TETROMINO, BITBOARD_WEEKDAY,
+    };
+    use chrono::NaiveDate;
+
+    #[test]
+    fn variant_base() {
+        assert_eq!(base::<DragonFjord, 8>(), BITBOARD_STANDARD);
+        assert_eq!(base::<Tetromino, 9>(), BITBOARD_TETROMINO);
+        assert_eq!(base::<Weekday, 10>(), BITBOARD_WEEKDAY);
+    }
+
+    #[test]
+    fn board_labels() {
+        let labels = labels::<Weekday, 10>();
+        assert_eq!(labels[63].as_deref(), Some("Jan"));
+        assert_eq!(labels[63 - 8 * 2].as_deref(), Some("1"));
+        assert_eq!(labels[63 - (8 * 6 + 3)].as_deref(), Some("Sun"));
+        assert_eq!(labels.iter().flatten().count(), 12 + 31 + 7);
+        assert_eq!(
+            super::labels::<DragonFjord, 8>().iter().flatten().count(),
+            12 + 31
+        );
+    }
+
+    #[test]
+    fn svg_labels_date() {
+        let date = NaiveDate::from_ymd_opt(2020, 12, 25).unwrap();
+        let svg = svg::<DragonFjord, 8>(&DragonFjord::solve_once(date).unwrap());
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(">Dec</text>"));
+        assert!(svg.contains(">25</text>"));
+        assert_eq!(svg.matches("<text").count(), 2);
+        assert_eq!(svg.matches("<rect").count(), 43);
+    }
+}
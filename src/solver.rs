@@ -0,0 +1,551 @@
+use crate::bitboard::BitBoard;
+use crate::board::{Board, Solution};
+use crate::piece::Piece;
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// How many nodes to evaluate between checks of the (comparatively expensive) deadline
+const DEADLINE_CHECK_INTERVAL: u64 = 1024;
+
+/// Limits that bound how much work a solve is allowed to do
+///
+/// The default options impose no limits, which finds every solution.
+/// They also place the pieces in their original order (see [`PieceOrder`]).
+///
+/// Note: `deadline` relies on `std::time::Instant` which is unavailable on `wasm32-unknown-unknown`,
+/// so wasm callers should bound work with `max_nodes` and `cancel` instead.
+#[derive(Clone, Debug, Default)]
+pub struct SolveOptions {
+    /// Stop after finding this many solutions
+    pub max_solutions: Option<usize>,
+    /// Stop after evaluating this many boards (partial placements)
+    pub max_nodes: Option<u64>,
+    /// Stop once this instant has passed
+    pub deadline: Option<Instant>,
+    /// Stop as soon as this token is cancelled
+    pub cancel: Option<CancelToken>,
+    /// Order to place pieces in, which only applies when [`Board::solve_with`] creates a solver
+    ///
+    /// A [`Solver`] keeps the order it was created with (see [`Solver::with_order`]), including when resumed.
+    pub piece_order: PieceOrder,
+}
+
+/// Order that a [`Solver`] places pieces in
+///
+/// Solutions always list pieces in their original order, but the order they are found in differs.
+/// [`Board::solve`](crate::Board::solve) finds every solution with [`PieceOrder::FewestPlacements`],
+/// then sorts them into the order [`PieceOrder::Original`] finds them in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PieceOrder {
+    /// The order the pieces are given in, so solutions are found in the same order as always
+    #[default]
+    Original,
+    /// Pieces with the fewest valid placements on the starting board first, which prunes the search sooner
+    FewestPlacements,
+}
+
+impl SolveOptions {
+    /// Options that stop after the first solution
+    pub fn first() -> SolveOptions {
+        SolveOptions {
+            max_solutions: Some(1),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the status that should end the search, if any limit has been reached
+    ///
+    /// `nodes` is the number of boards evaluated so far, and `solutions` the number of solutions found.
+    pub(crate) fn check(&self, nodes: u64, solutions: usize) -> Option<SolveStatus> {
+        if let Some(cancel) = &self.cancel {
+            if cancel.is_cancelled() {
+                return Some(SolveStatus::Cancelled);
+            }
+        }
+        if matches!(self.max_solutions, Some(max) if solutions >= max) {
+            return Some(SolveStatus::Truncated);
+        }
+        if matches!(self.max_nodes, Some(max) if nodes >= max) {
+            return Some(SolveStatus::Truncated);
+        }
+        if let Some(deadline) = self.deadline {
+            if nodes.is_multiple_of(DEADLINE_CHECK_INTERVAL) && Instant::now() >= deadline {
+                return Some(SolveStatus::Truncated);
+            }
+        }
+        None
+    }
+}
+
+/// Indicates whether a solve explored the entire search space
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolveStatus {
+    /// Every solution was found
+    Complete,
+    /// The search stopped early because a solution, node, or time limit was reached
+    Truncated,
+    /// The search stopped early because it was cancelled
+    Cancelled,
+}
+
+/// Solutions found by a solve, along with how the solve ended
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolveResult {
+    pub solutions: Vec<Solution>,
+    pub status: SolveStatus,
+    /// Number of boards evaluated during the search
+    pub nodes: u64,
+}
+
+impl SolveResult {
+    pub fn is_complete(&self) -> bool {
+        self.status == SolveStatus::Complete
+    }
+}
+
+/// Cheaply cloneable flag for cancelling a solve from another thread (or a callback)
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Depth-first search over piece placements that can be paused, checkpointed, and resumed
+///
+/// Each call to [`Solver::run`] continues where the previous call stopped, so a long enumeration
+/// can be split into chunks bounded by [`SolveOptions`]. Between chunks, the search state can be
+/// saved with [`Solver::write_state`] and later restored with [`Solver::read_state`].
+#[derive(Clone, Debug)]
+pub struct Solver<'a, const N: usize> {
+    pieces: Vec<Piece<'a>>,
+    /// Order pieces are placed in: any pieces already on the starting board, then the rest
+    order: Vec<usize>,
+    stack: Vec<Board<N>>,
+    nodes: u64,
+    solution_count: u64,
+}
+
+impl<'a, const N: usize> Solver<'a, N> {
+    pub fn new(board: Board<N>, pieces: &[Piece<'a>]) -> Solver<'a, N> {
+        Solver::with_order(board, pieces, PieceOrder::default())
+    }
+
+    /// Creates a solver that places the pieces in `piece_order`
+    pub fn with_order(
+        board: Board<N>,
+        pieces: &[Piece<'a>],
+        piece_order: PieceOrder,
+    ) -> Solver<'a, N> {
+        let (mut order, mut unplaced): (Vec<usize>, Vec<usize>) =
+            (0..N).partition(|&i| board.is_placed(i));
+        if piece_order == PieceOrder::FewestPlacements {
+            let mut buf = Vec::new();
+            // Stable, so pieces with as many placements keep their original order
+            unplaced.sort_by_cached_key(|&i| {
+                buf.clear();
+                board.append_valid_placements(i, pieces[i], &mut buf);
+                buf.len()
+            });
+        }
+        order.extend(unplaced);
+        Solver {
+            pieces: pieces.to_vec(),
+            order,
+            stack: vec![board],
+            nodes: 0,
+            solution_count: 0,
+        }
+    }
+
+    /// Returns true once the entire search space has been explored
+    pub fn is_done(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Total number of boards evaluated across all runs
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Total number of solutions found across all runs
+    pub fn solution_count(&self) -> u64 {
+        self.solution_count
+    }
+
+    /// Continues the search until it completes or a limit in `options` is reached
+    ///
+    /// Limits apply to this run only, and the result only contains solutions found during this run.
+    pub fn run(&mut self, options: &SolveOptions) -> SolveResult {
+        let mut solutions = Vec::new();
+        let mut nodes = 0;
+
+        while let Some(board) = self.stack.pop() {
+            nodes += 1;
+            if board.is_solved() {
+                solutions.push(Solution(board.pieces.into()));
+            } else {
+                let index = self.order[board.piece_count];
+                board.append_valid_placements(index, self.pieces[index], &mut self.stack);
+            }
+
+            // Only a search with work remaining can be cut short
+            if !self.stack.is_empty() {
+                if let Some(status) = options.check(nodes, solutions.len()) {
+                    return self.finish_run(solutions, status, nodes);
+                }
+            }
+        }
+
+        self.finish_run(solutions, SolveStatus::Complete, nodes)
+    }
+
+    fn finish_run(
+        &mut self,
+        solutions: Vec<Solution>,
+        status: SolveStatus,
+        nodes: u64,
+    ) -> SolveResult {
+        self.nodes += nodes;
+        self.solution_count += solutions.len() as u64;
+        SolveResult {
+            solutions,
+            status,
+            nodes,
+        }
+    }
+
+    /// Writes the search state as text
+    ///
+    /// The pieces are not included, so the state must be restored with the same pieces.
+    pub fn write_state<W: Write>(&self, mut w: W) -> io::Result<()> {
+        writeln!(
+            w,
+            "solver v2 {} {} {} {}",
+            N,
+            self.nodes,
+            self.solution_count,
+            self.stack.len()
+        )?;
+        let order: Vec<String> = self.order.iter().map(|i| i.to_string()).collect();
+        writeln!(w, "{}", order.join(" "))?;
+        for board in &self.stack {
+            write!(w, "{:016x} {}", board.combined.0, board.piece_count)?;
+            for piece in &board.pieces {
+                write!(w, " {:016x}", piece.0)?;
+            }
+            writeln!(w)?;
+        }
+        Ok(())
+    }
+
+    /// Restores a search state previously written by [`Solver::write_state`]
+    ///
+    /// States without a version (written before `v2` added the piece order) place pieces in their original order.
+    pub fn read_state<R: BufRead>(r: R, pieces: &[Piece<'a>]) -> io::Result<Solver<'a, N>> {
+        let mut lines = r.lines();
+        let header = lines
+            .next()
+            .ok_or_else(|| invalid_state("missing header"))??;
+        let header: Vec<&str> = header.split_whitespace().collect();
+        let (versioned, fields) = match header[..] {
+            ["solver", "v2", ref fields @ ..] => (true, fields),
+            ["solver", ref fields @ ..] => (false, fields),
+            _ => return Err(invalid_state("malformed header")),
+        };
+        let [n, nodes, solution_count, len] = fields[..] else {
+            return Err(invalid_state("malformed header"));
+        };
+        if parse_dec(n)? != N as u64 || pieces.len() != N {
+            return Err(invalid_state("piece count does not match"));
+        }
+
+        let order = if versioned {
+            let order = lines
+                .next()
+                .ok_or_else(|| invalid_state("missing order"))??;
+            order
+                .split_whitespace()
+                .map(|i| parse_dec(i).map(|i| i as usize))
+                .collect::<io::Result<Vec<_>>>()?
+        } else {
+            (0..N).collect()
+        };
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        if sorted != (0..N).collect::<Vec<_>>() {
+            return Err(invalid_state("malformed order"));
+        }
+
+        let len = parse_dec(len)? as usize;
+        let mut stack = Vec::with_capacity(len);
+        for _ in 0..len {
+            let line = lines
+                .next()
+                .ok_or_else(|| invalid_state("truncated stack"))??;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() != N + 2 {
+                return Err(invalid_state("malformed board"));
+            }
+            let mut board = Board::new(BitBoard(0), BitBoard(!0));
+            board.combined = BitBoard(parse_hex(fields[0])?);
+            board.piece_count = parse_dec(fields[1])? as usize;
+            if board.piece_count > N {
+                return Err(invalid_state("malformed board"));
+            }
+            for (piece, field) in board.pieces.iter_mut().zip(&fields[2..]) {
+                *piece = BitBoard(parse_hex(field)?);
+            }
+            stack.push(board);
+        }
+
+        Ok(Solver {
+            pieces: pieces.to_vec(),
+            order,
+            stack,
+            nodes: parse_dec(nodes)?,
+            solution_count: parse_dec(solution_count)?,
+        })
+    }
+}
+
+fn invalid_state(msg: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid solver state: {msg}"),
+    )
+}
+
+fn parse_dec(s: &str) -> io::Result<u64> {
+    s.parse().map_err(|_| invalid_state("expected a number"))
+}
+
+fn parse_hex(s: &str) -> io::Result<u64> {
+    u64::from_str_radix(s, 16).map_err(|_| invalid_state("expected a hex bitboard"))
+}
+
+/// A [`Solver`] for a board with any of the piece counts of the built-in variants
+///
+/// Created by [`VariantId::solver`](crate::variants::VariantId::solver) when the variant is chosen at runtime.
+#[derive(Clone, Debug)]
+pub enum AnySolver<'a> {
+    Eight(Solver<'a, 8>),
+    Nine(Solver<'a, 9>),
+    Ten(Solver<'a, 10>),
+}
+
+impl AnySolver<'_> {
+    /// See [`Solver::run`]
+    pub fn run(&mut self, options: &SolveOptions) -> SolveResult {
+        match self {
+            AnySolver::Eight(solver) => solver.run(options),
+            AnySolver::Nine(solver) => solver.run(options),
+            AnySolver::Ten(solver) => solver.run(options),
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        match self {
+            AnySolver::Eight(solver) => solver.is_done(),
+            AnySolver::Nine(solver) => solver.is_done(),
+            AnySolver::Ten(solver) => solver.is_done(),
+        }
+    }
+
+    pub fn nodes(&self) -> u64 {
+        match self {
+            AnySolver::Eight(solver) => solver.nodes(),
+            AnySolver::Nine(solver) => solver.nodes(),
+            AnySolver::Ten(solver) => solver.nodes(),
+        }
+    }
+
+    pub fn solution_count(&self) -> u64 {
+        match self {
+            AnySolver::Eight(solver) => solver.solution_count(),
+            AnySolver::Nine(solver) => solver.solution_count(),
+            AnySolver::Ten(solver) => solver.solution_count(),
+        }
+    }
+
+    /// Number of pieces in each solution
+    pub fn piece_count(&self) -> usize {
+        match self {
+            AnySolver::Eight(_) => 8,
+            AnySolver::Nine(_) => 9,
+            AnySolver::Ten(_) => 10,
+        }
+    }
+}
+
+impl<'a> From<Solver<'a, 8>> for AnySolver<'a> {
+    fn from(solver: Solver<'a, 8>) -> AnySolver<'a> {
+        AnySolver::Eight(solver)
+    }
+}
+
+impl<'a> From<Solver<'a, 9>> for AnySolver<'a> {
+    fn from(solver: Solver<'a, 9>) -> AnySolver<'a> {
+        AnySolver::Nine(solver)
+    }
+}
+
+impl<'a> From<Solver<'a, 10>> for AnySolver<'a> {
+    fn from(solver: Solver<'a, 10>) -> AnySolver<'a> {
+        AnySolver::Ten(solver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants::{DragonFjord, Variant};
+    use chrono::NaiveDate;
+
+    fn test_date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2020, 12, 25).unwrap()
+    }
+
+    #[test]
+    fn unlimited_is_complete() {
+        let result = DragonFjord::solve_with(test_date(), &SolveOptions::default());
+        assert_eq!(result.status, SolveStatus::Complete);
+        assert_eq!(
+            result.solutions.len(),
+            DragonFjord::solve_fully(test_date()).len()
+        );
+    }
+
+    #[test]
+    fn max_solutions_truncates() {
+        let options = SolveOptions {
+            max_solutions: Some(3),
+            ..Default::default()
+        };
+        let result = DragonFjord::solve_with(test_date(), &options);
+        assert_eq!(result.status, SolveStatus::Truncated);
+        assert_eq!(result.solutions.len(), 3);
+    }
+
+    #[test]
+    fn max_nodes_truncates() {
+        let options = SolveOptions {
+            max_nodes: Some(10),
+            ..Default::default()
+        };
+        let result = DragonFjord::solve_with(test_date(), &options);
+        assert_eq!(result.status, SolveStatus::Truncated);
+        assert_eq!(result.nodes, 10);
+    }
+
+    #[test]
+    fn expired_deadline_truncates() {
+        let options = SolveOptions {
+            deadline: Some(Instant::now()),
+            ..Default::default()
+        };
+        let result = DragonFjord::solve_with(test_date(), &options);
+        assert_eq!(result.status, SolveStatus::Truncated);
+    }
+
+    #[test]
+    fn cancelled_token_cancels() {
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let options = SolveOptions {
+            cancel: Some(cancel),
+            ..Default::default()
+        };
+        let result = DragonFjord::solve_with(test_date(), &options);
+        assert_eq!(result.status, SolveStatus::Cancelled);
+        assert_eq!(result.nodes, 1);
+    }
+
+    #[test]
+    fn piece_orders_find_the_same_solutions() {
+        let fewest = SolveOptions {
+            piece_order: PieceOrder::FewestPlacements,
+            ..Default::default()
+        };
+        let mut expected = DragonFjord::solve_fully(test_date());
+        let mut reordered = DragonFjord::solve_with(test_date(), &fewest).solutions;
+        assert_eq!(reordered.len(), expected.len());
+
+        // Solutions list pieces in their original order either way
+        let board = DragonFjord::board(test_date());
+        for solution in &reordered {
+            assert_eq!(
+                board.check_solution(&DragonFjord::pieces(), solution),
+                Ok(())
+            );
+        }
+        expected.sort_by_key(|s| s.0.iter().map(|bb| bb.0).collect::<Vec<_>>());
+        reordered.sort_by_key(|s| s.0.iter().map(|bb| bb.0).collect::<Vec<_>>());
+        assert_eq!(reordered, expected);
+    }
+
+    #[test]
+    fn resumed_solver_matches_uninterrupted() {
+        let expected = DragonFjord::solve_fully(test_date());
+
+        let pieces = DragonFjord::pieces();
+        let mut solver = Solver::new(DragonFjord::board(test_date()), &pieces);
+        let chunk = SolveOptions {
+            max_nodes: Some(500),
+            ..Default::default()
+        };
+        let mut solutions = Vec::new();
+        while !solver.is_done() {
+            solutions.extend(solver.run(&chunk).solutions);
+
+            let mut state = Vec::new();
+            solver.write_state(&mut state).unwrap();
+            solver = Solver::read_state(&state[..], &pieces).unwrap();
+        }
+
+        assert_eq!(solutions, expected);
+        assert_eq!(solver.solution_count(), expected.len() as u64);
+    }
+
+    #[test]
+    fn read_state_without_version() {
+        // The format before the piece order was saved, for a solver that has not started
+        let board = DragonFjord::board(test_date());
+        let state = format!(
+            "solver 8 0 0 1\n{:016x} 0{}\n",
+            board.combined.0,
+            " 0".repeat(8)
+        );
+
+        let pieces = DragonFjord::pieces();
+        let mut solver = Solver::<8>::read_state(state.as_bytes(), &pieces).unwrap();
+        assert_eq!(solver.order, (0..8).collect::<Vec<_>>());
+        let mut expected = Solver::with_order(board, &pieces, PieceOrder::Original);
+        assert_eq!(
+            solver.run(&SolveOptions::default()),
+            expected.run(&SolveOptions::default())
+        );
+    }
+
+    #[test]
+    fn read_state_rejects_mismatched_pieces() {
+        let pieces = DragonFjord::pieces();
+        let solver = Solver::new(DragonFjord::board(test_date()), &pieces);
+        let mut state = Vec::new();
+        solver.write_state(&mut state).unwrap();
+        assert!(Solver::<8>::read_state(&state[..], &pieces[..7]).is_err());
+        assert!(Solver::<9>::read_state(&state[..], &pieces).is_err());
+    }
+}
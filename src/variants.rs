@@ -1,7 +1,10 @@
 use crate::bitboard::BitBoard;
 use crate::board::{Board, Solution};
+use crate::db::SolutionDb;
 use crate::piece::*;
+use crate::solver::{AnySolver, PieceOrder, SolveOptions, SolveResult, Solver};
 use chrono::{Datelike, NaiveDate};
+use std::str::FromStr;
 
 /// Board use by:
 /// - DragonFjord [A-Puzzle-A-Day](https://www.dragonfjord.com/product/a-puzzle-a-day/)
@@ -54,29 +57,118 @@ pub const BITBOARD_TETROMINO: BitBoard = BitBoard(0x0303_0101_0101_F1FF);
 /// ```
 pub const BITBOARD_WEEKDAY: BitBoard = BitBoard(0x0303_0101_0101_01F1);
 
+/// Month and day squares to leave uncovered, plus the weekday square for boards that have one
+///
+/// Unlike [`NaiveDate`], any month and day on the board are allowed,
+/// so cells such as Feb 30 or Apr 31 can be solved as bonus challenges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BoardDate {
+    pub month: u32,
+    pub day: u32,
+    pub weekday: Option<chrono::Weekday>,
+}
+
+impl BoardDate {
+    pub fn new(month: u32, day: u32) -> BoardDate {
+        BoardDate {
+            month,
+            day,
+            weekday: None,
+        }
+    }
+
+    pub fn with_weekday(self, weekday: chrono::Weekday) -> BoardDate {
+        BoardDate {
+            weekday: Some(weekday),
+            ..self
+        }
+    }
+
+    /// Returns true if the month and day occur on a calendar (in leap years for Feb 29)
+    pub fn is_calendar_date(&self) -> bool {
+        NaiveDate::from_ymd_opt(2020, self.month, self.day).is_some()
+    }
+}
+
+impl From<NaiveDate> for BoardDate {
+    fn from(date: NaiveDate) -> BoardDate {
+        BoardDate::new(date.month(), date.day()).with_weekday(date.weekday())
+    }
+}
+
 pub trait Variant<const N: usize>: Sized {
-    fn board(date: NaiveDate) -> Board<N>;
-    fn pieces() -> [Piece; N];
+    /// Unique name that identifies the variant (e.g. in a [`SolutionDb`])
+    const NAME: &'static str;
 
+    /// Creates the board for any month and day on it (and weekday, if the board has weekdays)
+    fn board_at(date: BoardDate) -> Result<Board<N>, &'static str>;
+    fn pieces() -> [Piece<'static>; N];
+
+    fn board(date: NaiveDate) -> Board<N> {
+        Self::board_at(date.into()).expect("every calendar date is on the board")
+    }
+
+    /// Finds the first solution, using the installed [`SolutionDb`] if it has this date
     fn solve_once(date: NaiveDate) -> Option<Solution> {
-        Self::board(date)
-            .solve(&Self::pieces(), true)
-            .first()
-            .cloned()
+        Self::solve_once_at(date.into()).expect("every calendar date is on the board")
     }
 
+    /// Finds all solutions, using the installed [`SolutionDb`] if it has every solution for this date
     fn solve_fully(date: NaiveDate) -> Vec<Solution> {
-        Self::board(date).solve(&Self::pieces(), false)
+        Self::solve_fully_at(date.into()).expect("every calendar date is on the board")
+    }
+
+    /// Counts all solutions, using the installed [`SolutionDb`] if it has this date
+    fn count(date: NaiveDate) -> usize {
+        Self::count_at(date.into()).expect("every calendar date is on the board")
+    }
+
+    fn solve_with(date: NaiveDate, options: &SolveOptions) -> SolveResult {
+        Self::board(date).solve_with(&Self::pieces(), options)
+    }
+
+    /// Like [`Variant::solve_once`], but for any month and day on the board
+    fn solve_once_at(date: BoardDate) -> Result<Option<Solution>, &'static str> {
+        let board = Self::board_at(date)?;
+        if let Some(entry) = SolutionDb::global().and_then(|db| db.get_board::<Self, N>(board)) {
+            return Ok(entry.solutions.into_iter().next());
+        }
+        Ok(board.solve(&Self::pieces(), true).first().cloned())
+    }
+
+    /// Like [`Variant::solve_fully`], but for any month and day on the board
+    fn solve_fully_at(date: BoardDate) -> Result<Vec<Solution>, &'static str> {
+        let board = Self::board_at(date)?;
+        if let Some(entry) = SolutionDb::global().and_then(|db| db.get_board::<Self, N>(board)) {
+            if entry.is_complete() {
+                return Ok(entry.solutions);
+            }
+        }
+        Ok(board.solve(&Self::pieces(), false))
+    }
+
+    /// Like [`Variant::count`], but for any month and day on the board
+    fn count_at(date: BoardDate) -> Result<usize, &'static str> {
+        let board = Self::board_at(date)?;
+        match SolutionDb::global().and_then(|db| db.get_board::<Self, N>(board)) {
+            Some(entry) => Ok(entry.count as usize),
+            None => Ok(board.solve(&Self::pieces(), false).len()),
+        }
     }
 }
 
 pub struct DragonFjord;
 impl Variant<8> for DragonFjord {
-    fn board(date: NaiveDate) -> Board<8> {
-        Board::new(BITBOARD_STANDARD, standard_bitboard_from_date(date))
+    const NAME: &'static str = "dragon-fjord";
+
+    fn board_at(date: BoardDate) -> Result<Board<8>, &'static str> {
+        Ok(Board::new(
+            BITBOARD_STANDARD,
+            standard_bitboard(date.month, date.day)?,
+        ))
     }
 
-    fn pieces() -> [Piece; 8] {
+    fn pieces() -> [Piece<'static>; 8] {
         [
             PIECE_RECT.as_ref(),
             PIECE_U.as_ref(),
@@ -92,11 +184,16 @@ impl Variant<8> for DragonFjord {
 
 pub struct JarringWords;
 impl Variant<8> for JarringWords {
-    fn board(date: NaiveDate) -> Board<8> {
-        Board::new(BITBOARD_STANDARD, standard_bitboard_from_date(date))
+    const NAME: &'static str = "jarring-words";
+
+    fn board_at(date: BoardDate) -> Result<Board<8>, &'static str> {
+        Ok(Board::new(
+            BITBOARD_STANDARD,
+            standard_bitboard(date.month, date.day)?,
+        ))
     }
 
-    fn pieces() -> [Piece; 8] {
+    fn pieces() -> [Piece<'static>; 8] {
         [
             PIECE_RECT.as_ref(),
             PIECE_U.as_ref(),
@@ -112,11 +209,16 @@ impl Variant<8> for JarringWords {
 
 pub struct CreaMakerspace;
 impl Variant<8> for CreaMakerspace {
-    fn board(date: NaiveDate) -> Board<8> {
-        Board::new(BITBOARD_STANDARD, standard_bitboard_from_date(date))
+    const NAME: &'static str = "crea-makerspace";
+
+    fn board_at(date: BoardDate) -> Result<Board<8>, &'static str> {
+        Ok(Board::new(
+            BITBOARD_STANDARD,
+            standard_bitboard(date.month, date.day)?,
+        ))
     }
 
-    fn pieces() -> [Piece; 8] {
+    fn pieces() -> [Piece<'static>; 8] {
         [
             PIECE_H.as_ref(),
             PIECE_U.as_ref(),
@@ -132,11 +234,16 @@ impl Variant<8> for CreaMakerspace {
 
 pub struct Tetromino;
 impl Variant<9> for Tetromino {
-    fn board(date: NaiveDate) -> Board<9> {
-        Board::new(BITBOARD_TETROMINO, tetromino_bitboard_from_date(date))
+    const NAME: &'static str = "tetromino";
+
+    fn board_at(date: BoardDate) -> Result<Board<9>, &'static str> {
+        Ok(Board::new(
+            BITBOARD_TETROMINO,
+            tetromino_bitboard(date.month, date.day)?,
+        ))
     }
 
-    fn pieces() -> [Piece; 9] {
+    fn pieces() -> [Piece<'static>; 9] {
         [
             PIECE_SQUARE.as_ref(),
             PIECE_LINE.as_ref(),
@@ -153,11 +260,17 @@ impl Variant<9> for Tetromino {
 
 pub struct Weekday;
 impl Variant<10> for Weekday {
-    fn board(date: NaiveDate) -> Board<10> {
-        Board::new(BITBOARD_WEEKDAY, weekday_bitboard_from_date(date))
+    const NAME: &'static str = "weekday";
+
+    fn board_at(date: BoardDate) -> Result<Board<10>, &'static str> {
+        let weekday = date.weekday.ok_or("Weekday variant requires a weekday")?;
+        Ok(Board::new(
+            BITBOARD_WEEKDAY,
+            weekday_bitboard(date.month, date.day, weekday)?,
+        ))
     }
 
-    fn pieces() -> [Piece; 10] {
+    fn pieces() -> [Piece<'static>; 10] {
         [
             PIECE_LINE.as_ref(),
             PIECE_U.as_ref(),
@@ -173,53 +286,166 @@ impl Variant<10> for Weekday {
     }
 }
 
-/// Generates a standard bitboard with only the month and day cleared
-pub(crate) fn standard_bitboard_from_date(d: NaiveDate) -> BitBoard {
-    let month_part = match d.month() {
-        m @ 1..=6 => 1 << (16 - m),
-        m @ 7..=12 => 1 << (14 - m),
-        _ => unreachable!("Invalid month"),
-    };
-    let day_part = match d.day() {
+/// Identifies a variant chosen at runtime, e.g. by bindings that can't be generic over the variant
+#[cfg_attr(feature = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(eq, eq_int, frozen, hash, name = "Variant", module = "today_puzzle")
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VariantId {
+    DragonFjord = 0,
+    JarringWords = 1,
+    CreaMakerspace = 2,
+    Tetromino = 3,
+    Weekday = 4,
+}
+
+impl VariantId {
+    pub const ALL: [VariantId; 5] = [
+        VariantId::DragonFjord,
+        VariantId::JarringWords,
+        VariantId::CreaMakerspace,
+        VariantId::Tetromino,
+        VariantId::Weekday,
+    ];
+
+    /// The variant's [`Variant::NAME`]
+    pub fn name(self) -> &'static str {
+        match self {
+            VariantId::DragonFjord => DragonFjord::NAME,
+            VariantId::JarringWords => JarringWords::NAME,
+            VariantId::CreaMakerspace => CreaMakerspace::NAME,
+            VariantId::Tetromino => Tetromino::NAME,
+            VariantId::Weekday => Weekday::NAME,
+        }
+    }
+
+    /// The variant's [`Variant::pieces`]
+    pub fn pieces(self) -> Vec<Piece<'static>> {
+        match self {
+            VariantId::DragonFjord => DragonFjord::pieces().to_vec(),
+            VariantId::JarringWords => JarringWords::pieces().to_vec(),
+            VariantId::CreaMakerspace => CreaMakerspace::pieces().to_vec(),
+            VariantId::Tetromino => Tetromino::pieces().to_vec(),
+            VariantId::Weekday => Weekday::pieces().to_vec(),
+        }
+    }
+
+    /// Creates a solver for the variant's board on `date`
+    pub fn solver(self, date: BoardDate) -> Result<AnySolver<'static>, &'static str> {
+        self.solver_with_order(date, PieceOrder::Original)
+    }
+
+    /// Creates a solver for the variant's board on `date` that places the pieces in `piece_order`
+    pub fn solver_with_order(
+        self,
+        date: BoardDate,
+        piece_order: PieceOrder,
+    ) -> Result<AnySolver<'static>, &'static str> {
+        Ok(match self {
+            VariantId::DragonFjord => Solver::with_order(
+                DragonFjord::board_at(date)?,
+                &DragonFjord::pieces(),
+                piece_order,
+            )
+            .into(),
+            VariantId::JarringWords => Solver::with_order(
+                JarringWords::board_at(date)?,
+                &JarringWords::pieces(),
+                piece_order,
+            )
+            .into(),
+            VariantId::CreaMakerspace => Solver::with_order(
+                CreaMakerspace::board_at(date)?,
+                &CreaMakerspace::pieces(),
+                piece_order,
+            )
+            .into(),
+            VariantId::Tetromino => Solver::with_order(
+                Tetromino::board_at(date)?,
+                &Tetromino::pieces(),
+                piece_order,
+            )
+            .into(),
+            VariantId::Weekday => {
+                Solver::with_order(Weekday::board_at(date)?, &Weekday::pieces(), piece_order).into()
+            }
+        })
+    }
+}
+
+impl TryFrom<u32> for VariantId {
+    type Error = &'static str;
+
+    fn try_from(value: u32) -> Result<VariantId, &'static str> {
+        VariantId::ALL
+            .get(value as usize)
+            .copied()
+            .ok_or("Unknown variant")
+    }
+}
+
+impl FromStr for VariantId {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<VariantId, &'static str> {
+        VariantId::ALL
+            .into_iter()
+            .find(|id| id.name() == s)
+            .ok_or("Unknown variant")
+    }
+}
+
+/// Bit of the month square, shared by the standard, tetromino, and weekday boards
+fn month_bit(month: u32) -> Result<u64, &'static str> {
+    match month {
+        m @ 1..=6 => Ok(1 << (16 - m + 48)),
+        m @ 7..=12 => Ok(1 << (14 - m + 48)),
+        _ => Err("Invalid month"),
+    }
+}
+
+/// Generates a standard bitboard with only the given month and day cleared
+pub(crate) fn standard_bitboard(month: u32, day: u32) -> Result<BitBoard, &'static str> {
+    let day_part = match day {
         d @ 1..=7 => 1 << (48 - d),
         d @ 8..=14 => 1 << (47 - d),
         d @ 15..=21 => 1 << (46 - d),
         d @ 22..=28 => 1 << (45 - d),
         d @ 29..=31 => 1 << (44 - d),
-        _ => unreachable!("Invalid day"),
+        _ => return Err("Invalid day"),
     };
 
-    BitBoard(!((month_part << 48) | day_part))
+    Ok(BitBoard(!(month_bit(month)? | day_part)))
 }
 
-/// Generates a tetromino bitboard with only the month and day cleared
-pub(crate) fn tetromino_bitboard_from_date(d: NaiveDate) -> BitBoard {
-    let month_part = match d.month() {
-        m @ 1..=6 => 1 << (16 - m),
-        m @ 7..=12 => 1 << (14 - m),
-        _ => unreachable!("Invalid month"),
-    };
-    let day_part = match d.day() {
+/// Generates a tetromino bitboard with only the given month and day cleared
+pub(crate) fn tetromino_bitboard(month: u32, day: u32) -> Result<BitBoard, &'static str> {
+    let day_part = match day {
         d @ 1..=7 => 1 << (48 - d),
         d @ 8..=14 => 1 << (47 - d),
         d @ 15..=21 => 1 << (46 - d),
         d @ 22..=28 => 1 << (45 - d),
         d @ 29..=31 => 1 << (40 - d),
-        _ => unreachable!("Invalid day"),
+        _ => return Err("Invalid day"),
     };
 
-    BitBoard(!((month_part << 48) | day_part))
+    Ok(BitBoard(!(month_bit(month)? | day_part)))
 }
 
-/// Generates a weekday bitboard with only the month, day, and weekday cleared
-pub(crate) fn weekday_bitboard_from_date(d: NaiveDate) -> BitBoard {
-    let dow_part = match d.weekday().num_days_from_sunday() {
+/// Generates a weekday bitboard with only the given month, day, and weekday cleared
+pub(crate) fn weekday_bitboard(
+    month: u32,
+    day: u32,
+    weekday: chrono::Weekday,
+) -> Result<BitBoard, &'static str> {
+    let dow_part = match weekday.num_days_from_sunday() {
         d @ 0..=3 => 1 << (12 - d),
-        d @ 4..=6 => 1 << (7 - d),
-        _ => unreachable!("Invalid day of week"),
+        d => 1 << (7 - d),
     };
 
-    standard_bitboard_from_date(d) & BitBoard(!dow_part)
+    Ok(standard_bitboard(month, day)? & BitBoard(!dow_part))
 }
 
 #[cfg(test)]
@@ -276,12 +502,64 @@ mod tests {
     #[test]
     fn weekday_board() {
         assert_eq!(
-            !weekday_bitboard_from_date(ymd(2022, 1, 8)),
+            !weekday_bitboard(1, 8, chrono::Weekday::Sat).unwrap(),
             BitBoard(0x8000008000000002)
         ); // Sat
         assert_eq!(
-            !weekday_bitboard_from_date(ymd(2020, 1, 8)),
+            !weekday_bitboard(1, 8, chrono::Weekday::Wed).unwrap(),
             BitBoard(0x8000008000000200)
         ); // Wed
     }
+
+    #[test]
+    fn board_dates() {
+        assert_eq!(
+            BoardDate::from(ymd(2022, 1, 8)),
+            BoardDate::new(1, 8).with_weekday(chrono::Weekday::Sat)
+        );
+        assert_eq!(
+            DragonFjord::board_at(BoardDate::new(12, 1)),
+            Ok(DragonFjord::board(test_date()))
+        );
+        assert!(!BoardDate::new(2, 30).is_calendar_date());
+        assert!(BoardDate::new(2, 29).is_calendar_date());
+
+        assert_eq!(
+            DragonFjord::board_at(BoardDate::new(13, 1)),
+            Err("Invalid month")
+        );
+        assert_eq!(
+            Tetromino::board_at(BoardDate::new(1, 32)),
+            Err("Invalid day")
+        );
+        assert_eq!(
+            DragonFjord::board_at(BoardDate::new(1, 0)),
+            Err("Invalid day")
+        );
+        assert!(Weekday::board_at(BoardDate::new(1, 1)).is_err());
+    }
+
+    #[test]
+    fn invalid_calendar_dates() {
+        let solution = DragonFjord::solve_once_at(BoardDate::new(2, 30))
+            .unwrap()
+            .expect("did not find solution");
+        assert_solution(solution, 'A'..='H');
+        assert!(DragonFjord::count_at(BoardDate::new(4, 31)).unwrap() > 0);
+        assert!(DragonFjord::solve_once_at(BoardDate::new(0, 1)).is_err());
+    }
+
+    #[test]
+    fn variant_ids() {
+        assert_eq!(VariantId::try_from(3), Ok(VariantId::Tetromino));
+        assert!(VariantId::try_from(5).is_err());
+        assert_eq!("weekday".parse(), Ok(VariantId::Weekday));
+        assert!("weekdays".parse::<VariantId>().is_err());
+
+        let mut solver = VariantId::Tetromino.solver(BoardDate::new(12, 25)).unwrap();
+        assert_eq!(solver.piece_count(), 9);
+        let result = solver.run(&SolveOptions::first());
+        assert_solution(result.solutions[0].clone(), 'A'..='I');
+        assert!(VariantId::Weekday.solver(BoardDate::new(12, 25)).is_err());
+    }
 }
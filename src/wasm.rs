@@ -1,42 +1,506 @@
 #![allow(dead_code)]
 
-use crate::variants::{CreaMakerspace, DragonFjord, JarringWords, Tetromino, Variant, Weekday};
-use chrono::NaiveDateTime;
+use crate::board::Solution;
+use crate::challenge::Placement;
+use crate::render;
+use crate::solver::{AnySolver, CancelToken, SolveOptions, SolveStatus};
+use crate::variants::{
+    CreaMakerspace, DragonFjord, JarringWords, Tetromino, Variant, VariantId, Weekday,
+};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
 use wasm_bindgen::prelude::*;
 
+/// Describes a variant's board and pieces, so a frontend can draw it without knowing its layout
 #[wasm_bindgen]
-/// Finds the first solution for a given variant, and returns an array of piece bitmaps
-pub fn solve_once(epoch_ms: i64, variant: u32) -> Result<Box<[u64]>, String> {
-    let date = NaiveDateTime::from_timestamp_millis(epoch_ms)
-        .unwrap()
-        .date();
+pub struct VariantInfo {
+    id: VariantId,
+    name: &'static str,
+    board_mask: u64,
+    labels: Vec<String>,
+    piece_shapes: Vec<u16>,
+}
+
+#[wasm_bindgen]
+impl VariantInfo {
+    #[wasm_bindgen(constructor)]
+    pub fn new(id: VariantId) -> VariantInfo {
+        match id {
+            VariantId::DragonFjord => VariantInfo::of::<DragonFjord, 8>(id),
+            VariantId::JarringWords => VariantInfo::of::<JarringWords, 8>(id),
+            VariantId::CreaMakerspace => VariantInfo::of::<CreaMakerspace, 8>(id),
+            VariantId::Tetromino => VariantInfo::of::<Tetromino, 9>(id),
+            VariantId::Weekday => VariantInfo::of::<Weekday, 10>(id),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn id(&self) -> VariantId {
+        self.id
+    }
+
+    /// Name of the variant, as used by the command line (e.g. `dragon-fjord`)
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        self.name.to_owned()
+    }
+
+    /// Squares that are not part of the board, as a bitmap like the solution pieces
+    #[wasm_bindgen(getter)]
+    pub fn board_mask(&self) -> u64 {
+        self.board_mask
+    }
+
+    /// Label of each square (e.g. `Jan`, `31`, or `Sun`) from the top-left, row by row, or `""` for unlabeled squares
+    pub fn labels(&self) -> Vec<String> {
+        self.labels.clone()
+    }
+
+    /// 4x4 bitmap of each piece, in its first orientation
+    pub fn piece_shapes(&self) -> Box<[u16]> {
+        self.piece_shapes.clone().into_boxed_slice()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn piece_count(&self) -> usize {
+        self.piece_shapes.len()
+    }
+}
+
+impl VariantInfo {
+    fn of<V: Variant<N>, const N: usize>(id: VariantId) -> VariantInfo {
+        let labels = render::labels::<V, N>();
+        VariantInfo {
+            id,
+            name: V::NAME,
+            board_mask: render::base::<V, N>().0,
+            // Labels are indexed by bit, which counts from the bottom-right square
+            labels: (0..64)
+                .map(|i| labels[63 - i].clone().unwrap_or_default())
+                .collect(),
+            piece_shapes: V::pieces().iter().map(|p| p.variations[0].0).collect(),
+        }
+    }
+}
+
+/// Describes every variant, in the order of [`VariantId`]
+#[wasm_bindgen]
+pub fn variants() -> Vec<VariantInfo> {
+    VariantId::ALL.into_iter().map(VariantInfo::new).collect()
+}
+
+/// A solution, with the squares covered by each piece
+#[wasm_bindgen]
+pub struct SolutionGrid {
+    bitmaps: Vec<u64>,
+}
+
+#[wasm_bindgen]
+impl SolutionGrid {
+    /// Bitmap of the squares covered by each piece
+    pub fn bitmaps(&self) -> Box<[u64]> {
+        self.bitmaps.clone().into_boxed_slice()
+    }
+
+    /// Index of the piece covering each square from the top-left, row by row, or -1 for uncovered squares
+    pub fn cells(&self) -> Box<[i8]> {
+        (0..64)
+            .map(|i| {
+                let bit = 1 << (63 - i);
+                self.bitmaps
+                    .iter()
+                    .position(|bitmap| bitmap & bit != 0)
+                    .map_or(-1, |p| p as i8)
+            })
+            .collect()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn piece_count(&self) -> usize {
+        self.bitmaps.len()
+    }
+}
+
+impl From<&Solution> for SolutionGrid {
+    fn from(solution: &Solution) -> SolutionGrid {
+        SolutionGrid {
+            bitmaps: solution.0.iter().map(|bitboard| bitboard.0).collect(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+/// Finds the first solution for a given variant and date
+pub fn solve_once(
+    year: i32,
+    month: u32,
+    day: u32,
+    variant: VariantId,
+) -> Result<SolutionGrid, String> {
+    solve_date(date_from_ymd(year, month, day)?, variant)
+}
+
+#[wasm_bindgen]
+/// Like [`solve_once`], but for the date of a timestamp in the given timezone
+///
+/// `timezone_offset` is in minutes behind UTC, as returned by `Date.getTimezoneOffset()`.
+pub fn solve_once_at_time(
+    epoch_ms: i64,
+    timezone_offset: i32,
+    variant: VariantId,
+) -> Result<SolutionGrid, String> {
+    solve_date(date_from_epoch(epoch_ms, timezone_offset)?, variant)
+}
+
+fn solve_date(date: NaiveDate, variant: VariantId) -> Result<SolutionGrid, String> {
     let solution = match variant {
-        0 => DragonFjord::solve_once(date),
-        1 => JarringWords::solve_once(date),
-        2 => CreaMakerspace::solve_once(date),
-        3 => Tetromino::solve_once(date),
-        4 => Weekday::solve_once(date),
-        _ => unimplemented!("Unsupported variant"),
+        VariantId::DragonFjord => DragonFjord::solve_once(date),
+        VariantId::JarringWords => JarringWords::solve_once(date),
+        VariantId::CreaMakerspace => CreaMakerspace::solve_once(date),
+        VariantId::Tetromino => Tetromino::solve_once(date),
+        VariantId::Weekday => Weekday::solve_once(date),
     };
 
     match solution {
-        Some(s) => Ok(s
-            .0
-            .iter()
-            .map(|bitboard| bitboard.0)
-            .collect::<Vec<u64>>()
-            .into_boxed_slice()),
-        None => Err(format!("No solution for variant {variant} on {date}")),
-    }
-}
-
-// #[wasm_bindgen]
-// pub fn solve_fully(month: u32, day: u32, variant: u32) -> Board {
-//     let date = NaiveDate::from_ymd_opt(2020, month, day).unwrap();
-//     let solution = match variant {
-//         0 => DragonFjord::solve_once(date),
-//         1 => CreaMakerspace::solve_once(date),
-//     };
-
-//     solution.unwrap()
-// }
+        Some(s) => Ok(SolutionGrid::from(&s)),
+        None => Err(format!("No solution for variant {variant:?} on {date}")),
+    }
+}
+
+/// How a partly played board can be completed, as found by [`analyze_board`]
+#[wasm_bindgen]
+pub struct BoardAnalysis {
+    completions: u64,
+    complete: bool,
+    suggestion: Option<Placement>,
+}
+
+#[wasm_bindgen]
+impl BoardAnalysis {
+    /// Returns true if the placed pieces are part of at least one solution found
+    #[wasm_bindgen(getter)]
+    pub fn can_complete(&self) -> bool {
+        self.completions > 0
+    }
+
+    /// Number of solutions found that include the placed pieces
+    #[wasm_bindgen(getter)]
+    pub fn completions(&self) -> u64 {
+        self.completions
+    }
+
+    /// Returns false if the search stopped at `max_nodes`, so [`BoardAnalysis::completions`] is only a lower bound
+    #[wasm_bindgen(getter)]
+    pub fn complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Placement of the next unplaced piece in the first completion, as `[piece, variation, x, y]`
+    pub fn suggestion(&self) -> Option<Box<[u32]>> {
+        let p = self.suggestion?;
+        Some([p.piece, p.variation, p.x, p.y].map(|n| n as u32).into())
+    }
+}
+
+#[wasm_bindgen]
+/// Checks how a board with some pieces already placed can be completed, e.g. to play along in the browser
+///
+/// `placements` has four numbers per placed piece: the piece index, variation index, x, and y,
+/// as in [`crate::challenge::Placement`] (so x and y count from the bottom-right square).
+///
+/// Counting the completions of a board with few pieces placed can take seconds, so either run it in a
+/// Web Worker or pass `max_nodes` to give up after exploring that many boards (see [`BoardAnalysis::complete`]).
+pub fn analyze_board(
+    year: i32,
+    month: u32,
+    day: u32,
+    variant: VariantId,
+    placements: &[u32],
+    max_nodes: Option<u64>,
+) -> Result<BoardAnalysis, String> {
+    let date = date_from_ymd(year, month, day)?;
+    if !placements.len().is_multiple_of(4) {
+        return Err("Placements must have four numbers per piece".to_owned());
+    }
+    let placements: Vec<Placement> = placements
+        .chunks_exact(4)
+        .map(|p| Placement {
+            piece: p[0] as usize,
+            variation: p[1] as usize,
+            x: p[2] as usize,
+            y: p[3] as usize,
+        })
+        .collect();
+
+    let analysis = match variant {
+        VariantId::DragonFjord => analyze::<DragonFjord, 8>(date, &placements, max_nodes),
+        VariantId::JarringWords => analyze::<JarringWords, 8>(date, &placements, max_nodes),
+        VariantId::CreaMakerspace => analyze::<CreaMakerspace, 8>(date, &placements, max_nodes),
+        VariantId::Tetromino => analyze::<Tetromino, 9>(date, &placements, max_nodes),
+        VariantId::Weekday => analyze::<Weekday, 10>(date, &placements, max_nodes),
+    };
+    analysis.map_err(str::to_owned)
+}
+
+fn analyze<V: Variant<N>, const N: usize>(
+    date: NaiveDate,
+    placements: &[Placement],
+    max_nodes: Option<u64>,
+) -> Result<BoardAnalysis, &'static str> {
+    let pieces = V::pieces();
+    let board = placements
+        .iter()
+        .try_fold(V::board(date), |board, p| p.place(&board, &pieces))?;
+    let options = SolveOptions {
+        max_nodes,
+        ..Default::default()
+    };
+    let result = board.solve_with(&pieces, &options);
+    let solutions = result.solutions;
+
+    let suggestion = solutions.first().and_then(|solution| {
+        let piece = (0..N).find(|&i| !board.is_placed(i))?;
+        let (variation, x, y) = pieces[piece]
+            .find_placement(solution.0[piece])
+            .expect("solution piece matches a variation");
+        Some(Placement {
+            piece,
+            variation,
+            x,
+            y,
+        })
+    });
+    Ok(BoardAnalysis {
+        completions: solutions.len() as u64,
+        complete: result.status == SolveStatus::Complete,
+        suggestion,
+    })
+}
+
+/// Finds the solutions for a given variant and date incrementally
+///
+/// Every call runs to completion before returning, so run it in a Web Worker
+/// and use [`SolutionStream::next_solution_within`] to check for messages (e.g. to cancel) between calls.
+#[wasm_bindgen]
+pub struct SolutionStream {
+    solver: AnySolver<'static>,
+    cancel: CancelToken,
+}
+
+#[wasm_bindgen]
+impl SolutionStream {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        year: i32,
+        month: u32,
+        day: u32,
+        variant: VariantId,
+    ) -> Result<SolutionStream, String> {
+        SolutionStream::for_date(date_from_ymd(year, month, day)?, variant)
+    }
+
+    /// Like [`SolutionStream::new`], but for the date of a timestamp in the given timezone
+    ///
+    /// `timezone_offset` is in minutes behind UTC, as returned by `Date.getTimezoneOffset()`.
+    pub fn at_time(
+        epoch_ms: i64,
+        timezone_offset: i32,
+        variant: VariantId,
+    ) -> Result<SolutionStream, String> {
+        SolutionStream::for_date(date_from_epoch(epoch_ms, timezone_offset)?, variant)
+    }
+
+    /// Finds the next solution, or returns `None` once every solution has been found or the stream is cancelled
+    pub fn next_solution(&mut self) -> Option<SolutionGrid> {
+        self.search(SolveOptions::first())
+    }
+
+    /// Like [`SolutionStream::next_solution`], but gives up after exploring `max_nodes` boards
+    ///
+    /// Returns `None` both when giving up and when finished, so check [`SolutionStream::is_done`].
+    pub fn next_solution_within(&mut self, max_nodes: u64) -> Option<SolutionGrid> {
+        self.search(SolveOptions {
+            max_nodes: Some(max_nodes),
+            ..SolveOptions::first()
+        })
+    }
+
+    /// Number of solutions found so far
+    pub fn count_so_far(&self) -> u64 {
+        self.solver.solution_count()
+    }
+
+    /// Number of boards explored so far
+    pub fn nodes(&self) -> u64 {
+        self.solver.nodes()
+    }
+
+    /// Returns true once every solution has been found or the stream is cancelled
+    pub fn is_done(&self) -> bool {
+        self.solver.is_done() || self.cancel.is_cancelled()
+    }
+
+    /// Stops the search, so later calls find no more solutions
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+}
+
+impl SolutionStream {
+    fn for_date(date: NaiveDate, variant: VariantId) -> Result<SolutionStream, String> {
+        Ok(SolutionStream {
+            solver: variant.solver(date.into())?,
+            cancel: CancelToken::new(),
+        })
+    }
+
+    fn search(&mut self, options: SolveOptions) -> Option<SolutionGrid> {
+        if self.is_done() {
+            return None;
+        }
+        let options = SolveOptions {
+            cancel: Some(self.cancel.clone()),
+            ..options
+        };
+        let result = self.solver.run(&options);
+        result.solutions.first().map(SolutionGrid::from)
+    }
+}
+
+fn date_from_ymd(year: i32, month: u32, day: u32) -> Result<NaiveDate, String> {
+    NaiveDate::from_ymd_opt(year, month, day)
+        .ok_or_else(|| format!("Invalid date {year}-{month}-{day}"))
+}
+
+/// Local date of a timestamp, where `timezone_offset` is in minutes behind UTC
+fn date_from_epoch(epoch_ms: i64, timezone_offset: i32) -> Result<NaiveDate, String> {
+    NaiveDateTime::from_timestamp_millis(epoch_ms)
+        .and_then(|utc| utc.checked_sub_signed(Duration::minutes(timezone_offset.into())))
+        .map(|local| local.date())
+        .ok_or_else(|| format!("Timestamp {epoch_ms} is out of range"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2020-12-25T00:00:00Z
+    const CHRISTMAS: i64 = 1_608_854_400_000;
+
+    #[test]
+    fn stream_finds_every_solution() {
+        let mut stream = SolutionStream::new(2020, 12, 25, VariantId::DragonFjord).unwrap();
+        let mut solutions = Vec::new();
+        while !stream.is_done() {
+            solutions.extend(stream.next_solution_within(1000));
+        }
+        assert_eq!(solutions.len(), 92);
+        assert_eq!(stream.count_so_far(), 92);
+        assert_eq!(
+            solutions[0].bitmaps(),
+            solve_once(2020, 12, 25, VariantId::DragonFjord)
+                .unwrap()
+                .bitmaps()
+        );
+        assert!(stream.next_solution().is_none());
+    }
+
+    #[test]
+    fn stream_cancel() {
+        let mut stream = SolutionStream::new(2020, 12, 25, VariantId::DragonFjord).unwrap();
+        assert!(stream.next_solution().is_some());
+        stream.cancel();
+        assert!(stream.is_done());
+        assert!(stream.next_solution().is_none());
+        assert_eq!(stream.count_so_far(), 1);
+    }
+
+    #[test]
+    fn local_dates() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(date_from_epoch(CHRISTMAS, 0), Ok(date(2020, 12, 25)));
+        // Evening of the 24th in New York, morning of the 25th in Tokyo
+        assert_eq!(date_from_epoch(CHRISTMAS, 300), Ok(date(2020, 12, 24)));
+        assert_eq!(date_from_epoch(CHRISTMAS - 1, -540), Ok(date(2020, 12, 25)));
+        assert!(date_from_epoch(i64::MAX, 0).is_err());
+        assert!(date_from_ymd(2021, 2, 29).is_err());
+
+        let mut stream = SolutionStream::at_time(CHRISTMAS, 300, VariantId::DragonFjord).unwrap();
+        assert_eq!(
+            stream.next_solution().unwrap().bitmaps(),
+            solve_once(2020, 12, 24, VariantId::DragonFjord)
+                .unwrap()
+                .bitmaps()
+        );
+        assert!(solve_once(2020, 13, 1, VariantId::DragonFjord).is_err());
+    }
+
+    #[test]
+    fn variant_info() {
+        let infos = variants();
+        assert_eq!(infos.len(), 5);
+        assert_eq!(infos[3].name(), "tetromino");
+        assert_eq!(infos[4].piece_count(), 10);
+        assert_eq!(infos[0].piece_shapes()[0], 0x0077);
+
+        // The top-right square is off every board, and the bottom row only has days on the tetromino board
+        let info = VariantInfo::new(VariantId::Tetromino);
+        assert_eq!(info.board_mask() & (1 << 56), 1 << 56);
+        let labels = info.labels();
+        assert_eq!(labels[0], "Jan");
+        assert_eq!(labels[7], "");
+        assert_eq!(labels[16], "1");
+        assert_eq!(labels[52], "29");
+    }
+
+    #[test]
+    fn solution_cells() {
+        let solution = solve_once(2020, 12, 25, VariantId::DragonFjord).unwrap();
+        let cells = solution.cells();
+        // Dec and 25 are left uncovered
+        let labels = VariantInfo::new(VariantId::DragonFjord).labels();
+        let uncovered: Vec<&str> = (0..64)
+            .filter(|&i| cells[i] == -1 && !labels[i].is_empty())
+            .map(|i| labels[i].as_str())
+            .collect();
+        assert_eq!(uncovered, ["Dec", "25"]);
+        assert_eq!(solution.piece_count(), 8);
+        assert!(cells.iter().all(|&p| p < 8));
+    }
+
+    #[test]
+    fn play_along() {
+        let analysis = analyze_board(2020, 12, 25, VariantId::DragonFjord, &[], None).unwrap();
+        assert_eq!(analysis.completions(), 92);
+        assert!(analysis.complete());
+        let first = analysis.suggestion().unwrap();
+        assert_eq!(first[0], 0);
+
+        // Following suggestions completes the board
+        let mut placed = first.to_vec();
+        loop {
+            let analysis =
+                analyze_board(2020, 12, 25, VariantId::DragonFjord, &placed, None).unwrap();
+            assert!(analysis.can_complete());
+            match analysis.suggestion() {
+                Some(next) => placed.extend(next.iter()),
+                None => break,
+            }
+        }
+        assert_eq!(placed.len(), 8 * 4);
+
+        // The rectangle over Mar-May and Sep-Nov cuts off Jun, since Dec is left uncovered
+        let analysis =
+            analyze_board(2020, 12, 25, VariantId::DragonFjord, &[0, 0, 3, 6], None).unwrap();
+        assert!(!analysis.can_complete());
+        assert!(analysis.suggestion().is_none());
+
+        // Giving up early only counts the completions found so far
+        let analysis = analyze_board(2020, 12, 25, VariantId::DragonFjord, &[], Some(100)).unwrap();
+        assert!(!analysis.complete());
+        assert!(analysis.completions() < 92);
+
+        assert!(analyze_board(2020, 12, 25, VariantId::DragonFjord, &[0, 0, 0], None).is_err());
+        assert!(analyze_board(2020, 12, 25, VariantId::DragonFjord, &[0, 9, 0, 0], None).is_err());
+        assert!(analyze_board(2020, 12, 25, VariantId::DragonFjord, &[0, 0, 7, 0], None).is_err());
+    }
+}
@@ -0,0 +1,55 @@
+// Exercises the C API (see `just test-ffi`)
+#include <assert.h>
+#include <inttypes.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "today_puzzle.h"
+
+// Prints a solution with a letter per piece, like the today-is command line tool
+static void print_solution(const uint64_t *pieces, uintptr_t count) {
+  for (int i = 0; i < 64; i++) {
+    char c = '.';
+    for (uintptr_t p = 0; p < count; p++) {
+      if (pieces[p] & ((uint64_t)1 << (63 - i))) {
+        c = (char)('A' + p);
+      }
+    }
+    printf("%c%c", c, i % 8 == 7 ? '\n' : ' ');
+  }
+}
+
+int main(void) {
+  assert(tp_variant_count() == 5);
+  assert(strcmp(tp_variant_name(TP_VARIANT_DRAGON_FJORD), "dragon-fjord") == 0);
+  assert(strcmp(tp_variant_name(TP_VARIANT_WEEKDAY), "weekday") == 0);
+  assert(tp_variant_name(5) == NULL);
+
+  assert(tp_solver_new(TP_VARIANT_DRAGON_FJORD, 2021, 2, 29) == NULL);
+  assert(tp_solver_new(5, 2020, 12, 25) == NULL);
+  assert(tp_count_solutions(5, 2020, 12, 25) == -1);
+
+  int64_t expected = tp_count_solutions(TP_VARIANT_DRAGON_FJORD, 2020, 12, 25);
+  assert(expected == 92);
+
+  TpSolver *solver = tp_solver_new(TP_VARIANT_DRAGON_FJORD, 2020, 12, 25);
+  assert(solver != NULL);
+  uintptr_t piece_count = tp_solver_piece_count(solver);
+  assert(piece_count == 8);
+
+  uint64_t pieces[10];
+  uint64_t found = 0;
+  while (tp_solver_next(solver, pieces, 10) == piece_count) {
+    if (found == 0) {
+      print_solution(pieces, piece_count);
+    }
+    found++;
+  }
+  assert(found == (uint64_t)expected);
+  assert(tp_solver_count(solver) == found);
+  tp_solver_free(solver);
+  tp_solver_free(NULL);
+
+  printf("2020-12-25 has %" PRIu64 " solutions\n", found);
+  return 0;
+}
@@ -0,0 +1,70 @@
+"""Tests for the Python bindings (see `just test-python`)"""
+import datetime
+import unittest
+
+import today_puzzle
+from today_puzzle import Variant
+
+CHRISTMAS = datetime.date(2020, 12, 25)
+
+
+class VariantTest(unittest.TestCase):
+    def test_variants(self):
+        variants = today_puzzle.variants()
+        self.assertEqual(len(variants), 5)
+        self.assertEqual(variants[0], Variant.DragonFjord)
+        self.assertEqual(
+            [v.name for v in variants],
+            ["dragon-fjord", "jarring-words", "crea-makerspace", "tetromino", "weekday"],
+        )
+        self.assertEqual(Variant.Weekday.piece_count, 10)
+
+    def test_from_name(self):
+        self.assertEqual(Variant.from_name("tetromino"), Variant.Tetromino)
+        with self.assertRaises(ValueError):
+            Variant.from_name("tetrominoes")
+
+
+class SolveTest(unittest.TestCase):
+    def test_solve_once(self):
+        solution = today_puzzle.solve_once(Variant.DragonFjord, CHRISTMAS)
+        self.assertEqual(solution.variant, Variant.DragonFjord)
+        self.assertEqual(len(solution), 8)
+        self.assertEqual(str(solution).splitlines()[0], "A A G G G G . . ")
+
+    def test_solve_fully(self):
+        solutions = today_puzzle.solve_fully(Variant.DragonFjord, CHRISTMAS)
+        self.assertEqual(len(solutions), 92)
+        self.assertEqual(today_puzzle.count(Variant.DragonFjord, CHRISTMAS), 92)
+        self.assertEqual(len({tuple(s.bitmaps) for s in solutions}), 92)
+
+    def test_bitmaps(self):
+        solution = today_puzzle.solve_once(Variant.Tetromino, CHRISTMAS)
+        bitmaps = solution.bitmaps
+        self.assertEqual(len(bitmaps), 9)
+        # Bitmaps fit in a u64 (e.g. for numpy.uint64), and pieces don't overlap
+        covered = 0
+        for bitmap in bitmaps:
+            self.assertTrue(0 < bitmap < 2**64)
+            self.assertEqual(covered & bitmap, 0)
+            covered |= bitmap
+
+    def test_placements(self):
+        solution = today_puzzle.solve_once(Variant.Weekday, CHRISTMAS)
+        placements = solution.placements
+        self.assertEqual([p[0] for p in placements], list(range(10)))
+        for piece, variation, x, y in placements:
+            self.assertGreaterEqual(variation, 0)
+            self.assertTrue(0 <= x < 8 and 0 <= y < 8)
+
+    def test_datetime(self):
+        # Accepts datetimes too, since they are dates
+        moment = datetime.datetime(2020, 12, 25, 23, 59)
+        self.assertEqual(
+            today_puzzle.solve_once(Variant.DragonFjord, moment).bitmaps,
+            today_puzzle.solve_once(Variant.DragonFjord, CHRISTMAS).bitmaps,
+        )
+
+
+if __name__ == "__main__":
+    unittest.main()
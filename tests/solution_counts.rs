@@ -0,0 +1,89 @@
+//! Regression tests comparing solver output against stored solution counts for dates of 2020
+//!
+//! Enumerating every solution for every date is slow, so these tests are ignored by default.
+//! Run them with `cargo test --release -- --ignored`.
+//!
+//! Fixtures use the same format as `--all-dates --print count` and can be regenerated with:
+//! `just run --all-dates --print count --variant <variant> > tests/fixtures/<variant>.txt`
+
+use chrono::NaiveDate;
+use today_puzzle::calendar::WeekdayTable;
+use today_puzzle::variants::{
+    CreaMakerspace, DragonFjord, JarringWords, Tetromino, Variant, Weekday,
+};
+
+/// Parses lines formatted as `MM-DD has N solutions`
+fn parse_counts(fixture: &str) -> Vec<(NaiveDate, usize)> {
+    fixture
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let ["has", count, "solutions"] = words[1..] else {
+                panic!("malformed fixture line: {line}");
+            };
+            let date = NaiveDate::parse_from_str(&format!("2020-{}", words[0]), "%Y-%m-%d")
+                .unwrap_or_else(|_| panic!("malformed fixture date: {line}"));
+            (date, count.parse().unwrap())
+        })
+        .collect()
+}
+
+fn assert_counts<V: Variant<N>, const N: usize>(fixture: &str) {
+    let expected = parse_counts(fixture);
+    assert!(!expected.is_empty(), "fixture has no counts");
+
+    let mismatches: Vec<String> = expected
+        .into_iter()
+        .filter_map(|(date, count)| {
+            let actual = V::count(date);
+            (actual != count).then(|| format!("{date}: expected {count}, found {actual}"))
+        })
+        .collect();
+    assert!(
+        mismatches.is_empty(),
+        "{} has mismatched counts:\n{}",
+        V::NAME,
+        mismatches.join("\n")
+    );
+}
+
+#[test]
+#[ignore = "slow: enumerates every solution for every date"]
+fn dragon_fjord() {
+    assert_counts::<DragonFjord, 8>(include_str!("../count_solutions.txt"));
+}
+
+#[test]
+#[ignore = "slow: enumerates every solution for every date"]
+fn jarring_words() {
+    assert_counts::<JarringWords, 8>(include_str!("fixtures/jarring-words.txt"));
+}
+
+#[test]
+#[ignore = "slow: enumerates every solution for every date"]
+fn crea_makerspace() {
+    assert_counts::<CreaMakerspace, 8>(include_str!("fixtures/crea-makerspace.txt"));
+}
+
+#[test]
+#[ignore = "slow: enumerates every solution for every date"]
+fn tetromino() {
+    assert_counts::<Tetromino, 9>(include_str!("fixtures/tetromino.txt"));
+}
+
+// The fixture covers every date of 2020, and counting takes about 3s per Weekday date
+// in release builds (about 20 minutes with `cargo test --release -- --ignored weekday`)
+#[test]
+#[ignore = "slow: enumerates every solution for every date"]
+fn weekday() {
+    assert_counts::<Weekday, 10>(include_str!("fixtures/weekday.txt"));
+}
+
+// Regenerate with `just run weekdays --table tests/fixtures/weekday-table.txt` (after removing the old file)
+#[test]
+#[ignore = "slow: solves every (month, day, weekday) combination"]
+fn weekday_table() {
+    let expected = WeekdayTable::read(&include_bytes!("fixtures/weekday-table.txt")[..]).unwrap();
+    assert_eq!(WeekdayTable::compute(), expected);
+}